@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+/// Identity of a file's content as far as the filesystem can tell. A cached
+/// hash is only reused while all of these still match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub dev: u64,
+    pub ino: u64,
+    pub size: u64,
+    /// Modification time in nanoseconds since the Unix epoch.
+    pub mtime: u128,
+}
+
+impl Fingerprint {
+    pub fn of(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let (dev, ino) = dev_ino(&metadata);
+
+        Ok(Fingerprint {
+            dev,
+            ino,
+            size: metadata.len(),
+            mtime,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    fingerprint: Fingerprint,
+    /// Hashes by key, e.g. `blake3` or `blake3:partial`.
+    hashes: HashMap<String, String>,
+}
+
+/// Hashes of previously scanned files, keyed by path and invalidated as soon
+/// as a file's fingerprint changes. Safe to share between hashing threads.
+#[derive(Debug, Default)]
+pub struct HashCache {
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+}
+
+impl HashCache {
+    /// Loads the cache stored at `path`. A missing or unreadable cache is
+    /// treated as empty.
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        HashCache {
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// Writes the cache to `path`. The temporary file carries the process id,
+    /// so concurrent runs never write to the same one.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let entries = self.entries.lock().unwrap();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string(&*entries).map_err(io::Error::other)?;
+        let temp = path.with_extension(format!("tmp.{}", std::process::id()));
+        if let Err(e) = fs::write(&temp, content).and_then(|()| fs::rename(&temp, path)) {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Drops entries under `roots` for files a scan of those roots no longer
+    /// found. Nothing is checked on disk, and entries elsewhere are kept.
+    pub fn prune(&self, roots: &[PathBuf], found: &HashSet<PathBuf>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|file, _| {
+            found.contains(file) || !roots.iter().any(|root| file.starts_with(root))
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, path: &Path, fingerprint: &Fingerprint, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(path)?;
+        if entry.fingerprint != *fingerprint {
+            return None;
+        }
+        entry.hashes.get(key).cloned()
+    }
+
+    pub fn insert(&self, path: &Path, fingerprint: Fingerprint, key: &str, hash: String) {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries
+            .entry(path.to_path_buf())
+            .or_insert_with(|| CacheEntry {
+                fingerprint,
+                hashes: HashMap::new(),
+            });
+
+        if entry.fingerprint != fingerprint {
+            entry.fingerprint = fingerprint;
+            entry.hashes.clear();
+        }
+        entry.hashes.insert(key.to_string(), hash);
+    }
+
+    /// Returns the cached hash for `path` under `key`, computing and storing
+    /// it with `compute` if the file changed or was never hashed.
+    pub fn get_or_compute<F>(&self, path: &Path, key: &str, compute: F) -> io::Result<String>
+    where
+        F: FnOnce() -> io::Result<String>,
+    {
+        let before = Fingerprint::of(path)?;
+        if let Some(hash) = self.get(path, &before, key) {
+            return Ok(hash);
+        }
+
+        let hash = compute()?;
+
+        // Only remember the hash if the file did not change while reading it.
+        if Fingerprint::of(path).is_ok_and(|after| after == before) {
+            self.insert(path, before, key, hash.clone());
+        }
+
+        Ok(hash)
+    }
+}
+
+#[cfg(unix)]
+fn dev_ino(metadata: &fs::Metadata) -> (u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn dev_ino(_metadata: &fs::Metadata) -> (u64, u64) {
+    (0, 0)
+}
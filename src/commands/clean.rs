@@ -1,34 +1,140 @@
-use crate::config::load_scan_mode;
-use crate::duplicates;
+use crate::config::{clear_session, load_session};
+use crate::options::{CleanAction, OutputFormat, ScanOptions};
+use crate::output;
 use crate::prompts;
+use crate::quarantine::{self, QuarantineLocation};
 use crate::report::Report;
+use crate::review;
 use crate::utils::{delete_quarantine_dir, process_quarantined_files};
+use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::PathBuf;
 
-pub fn run_clean(dir: &Path) -> io::Result<()> {
-    let scan_choice = match load_scan_mode() {
-        Some(mode) => mode,
-        None => {
-            eprintln!("❌ No scan mode found. Please run `deduck filter` first.");
-            return Err(io::Error::new(io::ErrorKind::Other, "No scan mode saved"));
+pub fn run_clean(
+    options: ScanOptions,
+    location: &QuarantineLocation,
+    action: Option<CleanAction>,
+    yes: bool,
+    review: bool,
+    dry_run: bool,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let session = match load_session() {
+        Some(session) if session.is_for(&options.roots) => session,
+        _ => {
+            eprintln!(
+                "❌ No filter results found for {}. Please run `deduck filter` first.",
+                display_roots(&options.roots)
+            );
+            return Err(io::Error::other("No filter results saved"));
         }
     };
 
-    let clean_choice = prompts::prompt_clean_choice()?;
-    let quarantine_dir = crate::quarantine::get_quarantine_dir(dir);
+    let clean_choice = prompts::resolve_clean_action(action, yes)?;
+    let quarantine_dirs = quarantine::quarantine_dirs(&options.roots, location);
 
-    let mut report = Report::new();
+    let mut report = if dry_run {
+        Report::dry_run()
+    } else {
+        Report::new()
+    };
+
+    let result = &session.result;
+    report.set_files_found(result.files_found);
+
+    let (candidates, kept) = if review {
+        let selection = review::review(result)?;
+        (selection.remove, selection.keep)
+    } else {
+        // JSON output is a single document: the report.
+        if !dry_run && format != OutputFormat::Json {
+            output::print_scan_result(format, result)?;
+        }
+        (result.redundant_files(), result.kept_files())
+    };
+
+    // Files may have changed since `filter` ran; only move what still matches.
+    let (to_quarantine, skipped) = session.verify(candidates, &kept);
+    for file in &skipped {
+        eprintln!("⚠️ Skipping {}: {}", file.path.display(), file.reason);
+    }
+
+    if dry_run {
+        for path in to_quarantine {
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            match clean_choice {
+                CleanAction::Clean => report.add_file(path, size),
+                CleanAction::Separate => report.add_moved(path, size),
+            }
+        }
+        // A real clean also deletes whatever was quarantined before.
+        if clean_choice == CleanAction::Clean {
+            for quarantine_dir in &quarantine_dirs {
+                process_quarantined_files(quarantine_dir, &mut report)?;
+            }
+        }
+        return output::print_report(format, &report);
+    }
+
+    let mut failure = None;
+    if !to_quarantine.is_empty() {
+        let result =
+            quarantine::quarantine_by_root(to_quarantine, &options.roots, location, &mut report);
+        if let Err(e) = result {
+            eprintln!("❌ Failed to quarantine files: {}", e);
+            failure = Some(e);
+        }
+        if clean_choice == CleanAction::Separate {
+            super::register_quarantines(&quarantine_dirs);
+        }
+    }
+
+    if clean_choice == CleanAction::Separate {
+        let moved: Vec<(PathBuf, u64)> = report
+            .moves
+            .iter()
+            .filter(|m| m.method.is_some())
+            .map(|m| {
+                let size = fs::metadata(&m.destination)
+                    .map(|meta| meta.len())
+                    .unwrap_or(0);
+                (m.path.clone(), size)
+            })
+            .collect();
+        for (path, size) in moved {
+            report.add_moved(path, size);
+        }
+    } else {
+        for quarantine_dir in &quarantine_dirs {
+            process_quarantined_files(quarantine_dir, &mut report)?;
+        }
+    }
+
+    // Keep the filter results for a retry if some files could not be moved.
+    if failure.is_none()
+        && let Err(e) = clear_session()
+    {
+        eprintln!("Warning: failed to clear filter results: {}", e);
+    }
 
-    let files_found = duplicates::duplicates(dir, scan_choice, true)?;
+    if clean_choice == CleanAction::Clean {
+        for quarantine_dir in &quarantine_dirs {
+            delete_quarantine_dir(quarantine_dir)?;
+        }
+    }
 
-    report.set_files_found(files_found);
-    process_quarantined_files(&quarantine_dir, &mut report)?;
+    output::print_report(format, &report)?;
 
-    if clean_choice == 1 {
-        delete_quarantine_dir(&quarantine_dir)?;
-        report.display();
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
     }
+}
 
-    Ok(())
+fn display_roots(roots: &[PathBuf]) -> String {
+    roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
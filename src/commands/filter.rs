@@ -1,18 +1,28 @@
-use crate::config::save_scan_mode;
-use crate::duplicates;
+use crate::config::save_session;
+use crate::options::{OutputFormat, ScanMode, ScanOptions};
+use crate::output;
 use crate::prompts;
+use crate::session::Session;
 use std::io;
-use std::path::Path;
 
-pub fn run_filter(dir: &Path) -> io::Result<()> {
-    let scan_choice = prompts::prompt_scan_mode()?;
+pub fn run_filter(
+    mut options: ScanOptions,
+    mode: Option<ScanMode>,
+    yes: bool,
+    format: OutputFormat,
+) -> io::Result<()> {
+    options.mode = prompts::resolve_scan_mode(mode, yes)?;
 
-    if let Err(e) = save_scan_mode(scan_choice) {
-        eprintln!("Warning: failed to save scan mode: {}", e);
-    }
+    match super::find_duplicates(&options) {
+        Ok(result) => {
+            output::print_scan_result(format, &result)?;
 
-    if let Err(e) = duplicates::duplicates(dir, scan_choice, false) {
-        eprintln!("❌ An error occurred during filtering: {}", e);
+            let session = Session::new(&options.roots, options.mode, result);
+            if let Err(e) = save_session(&session) {
+                eprintln!("Warning: failed to save filter results: {}", e);
+            }
+        }
+        Err(e) => eprintln!("❌ An error occurred during filtering: {}", e),
     }
 
     Ok(())
@@ -1,5 +1,36 @@
 pub mod clean;
 pub mod filter;
+pub mod link;
 pub mod purge;
+pub mod quarantine;
+pub mod reflink;
 pub mod restore;
 pub mod scan;
+pub mod symlink;
+pub mod tui;
+pub mod unlink;
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::duplicates::{self, ScanResult};
+use crate::options::ScanOptions;
+use crate::output;
+
+/// Runs a scan with the hashing progress bar attached.
+pub(crate) fn find_duplicates(options: &ScanOptions) -> io::Result<ScanResult> {
+    let pb = output::hashing_progress_bar();
+    let result = duplicates::find_duplicates_with_progress(options, &pb);
+    pb.finish_with_message("✅ Finished hashing files");
+    result
+}
+
+/// Remembers the quarantine folders in `dirs` that exist, so they can be
+/// listed later.
+pub(crate) fn register_quarantines(dirs: &[PathBuf]) {
+    let existing: Vec<PathBuf> = dirs.iter().filter(|dir| dir.exists()).cloned().collect();
+    if let Err(e) = config::register_quarantine_dirs(&existing) {
+        eprintln!("Warning: failed to record quarantine folders: {}", e);
+    }
+}
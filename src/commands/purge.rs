@@ -1,16 +1,36 @@
+use crate::options::OutputFormat;
+use crate::output;
+use crate::quarantine::{self, QuarantineLocation, Retention, unix_now};
 use crate::report::Report;
 use crate::utils::{delete_quarantine_dir, process_quarantined_files};
 use std::io;
-use std::path::Path;
+use std::path::PathBuf;
 
-pub fn run_purge(dir: &Path) -> io::Result<()> {
-    let quarantine_dir = crate::quarantine::get_quarantine_dir(dir);
+pub fn run_purge(
+    dirs: &[PathBuf],
+    location: &QuarantineLocation,
+    retention: &Retention,
+    dry_run: bool,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let mut report = if dry_run {
+        Report::dry_run()
+    } else {
+        Report::new()
+    };
 
-    let mut report = Report::new();
-    process_quarantined_files(&quarantine_dir, &mut report)?;
+    if !retention.is_unlimited() {
+        let quarantine_dirs = quarantine::quarantine_dirs(dirs, location);
+        quarantine::expire_quarantined(&quarantine_dirs, retention, unix_now(), &mut report)?;
+        return output::print_report(format, &report);
+    }
 
-    delete_quarantine_dir(&quarantine_dir)?;
-    report.display();
+    for quarantine_dir in quarantine::quarantine_dirs(dirs, location) {
+        process_quarantined_files(&quarantine_dir, &mut report)?;
 
-    Ok(())
+        if !dry_run {
+            delete_quarantine_dir(&quarantine_dir)?;
+        }
+    }
+    output::print_report(format, &report)
 }
@@ -0,0 +1,58 @@
+use crate::config;
+use crate::options::OutputFormat;
+use crate::output;
+use crate::quarantine::{self, QuarantineLocation};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Lists every quarantine folder deduck knows of: those it recorded, those
+/// in the configured and central stores, and those of `roots`.
+pub fn run_list(
+    roots: &[PathBuf],
+    location: &QuarantineLocation,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let mut dirs = config::load_quarantine_dirs();
+    dirs.extend(quarantine::quarantine_dirs(roots, location));
+
+    let stores = [
+        location.store().map(Path::to_path_buf),
+        config::central_quarantine_dir(),
+    ];
+    for store in stores.into_iter().flatten() {
+        if let Ok(entries) = fs::read_dir(&store) {
+            dirs.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir()),
+            );
+        }
+    }
+
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut summaries = Vec::new();
+    for dir in dirs {
+        let Ok(canonical) = fs::canonicalize(&dir) else {
+            continue;
+        };
+        if seen.contains(&canonical) {
+            continue;
+        }
+
+        match quarantine::summarize(&canonical) {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => eprintln!("⚠️ Could not read {}: {}", dir.display(), e),
+        }
+        seen.push(canonical);
+    }
+    summaries.sort_by(|a, b| a.dir.cmp(&b.dir));
+
+    // Folders that were restored or purged since are forgotten.
+    if let Err(e) = config::write_quarantine_dirs(&seen) {
+        eprintln!("Warning: failed to record quarantine folders: {}", e);
+    }
+
+    output::print_quarantines(format, &summaries)
+}
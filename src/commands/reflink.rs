@@ -0,0 +1,41 @@
+use crate::config::save_link_log;
+use crate::options::{OutputFormat, ScanMode, ScanOptions};
+use crate::output;
+use crate::prompts;
+use crate::reflink;
+use crate::report::Report;
+use std::io;
+
+pub fn run_reflink(
+    mut options: ScanOptions,
+    mode: Option<ScanMode>,
+    yes: bool,
+    dry_run: bool,
+    format: OutputFormat,
+) -> io::Result<()> {
+    options.mode = prompts::resolve_scan_mode(mode, yes)?;
+
+    let result = super::find_duplicates(&options)?;
+    // JSON output is a single document: the report.
+    if !dry_run && format != OutputFormat::Json {
+        output::print_scan_result(format, &result)?;
+    }
+
+    let mut report = if dry_run {
+        Report::dry_run()
+    } else {
+        Report::new()
+    };
+    report.set_files_found(result.files_found);
+    reflink::reflink_duplicates(&result.duplicates, &mut report)?;
+
+    if dry_run {
+        return output::print_report(format, &report);
+    }
+
+    if let Err(e) = save_link_log(&report.links) {
+        eprintln!("Warning: failed to save link log: {}", e);
+    }
+
+    output::print_report(format, &report)
+}
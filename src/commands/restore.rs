@@ -1,17 +1,58 @@
-use crate::quarantine;
+use crate::options::OutputFormat;
+use crate::output;
+use crate::quarantine::{self, QuarantineLocation};
+use crate::report::Report;
 use std::io;
-use std::path::Path;
+use std::path::PathBuf;
 
-pub fn run_restore(dir: &Path) -> io::Result<()> {
-    let quarantine_dir = quarantine::get_quarantine_dir(dir);
-    match quarantine::restore_quarantined(&quarantine_dir, dir) {
-        Ok(_) => {
-            println!("✅ Quarantined files restored.");
-            Ok(())
+pub fn run_restore(
+    dirs: &[PathBuf],
+    location: &QuarantineLocation,
+    dry_run: bool,
+    format: OutputFormat,
+) -> io::Result<()> {
+    let mut report = if dry_run {
+        Report::dry_run()
+    } else {
+        Report::new()
+    };
+
+    let mut found = false;
+    for dir in dirs {
+        let quarantine_dir = location.dir_for(dir);
+        if !quarantine_dir.exists() {
+            continue;
         }
-        Err(e) => {
+        found = true;
+
+        if let Err(e) = quarantine::restore_quarantined(&quarantine_dir, dir, &mut report) {
             eprintln!("❌ Failed to restore quarantined files: {}", e);
-            Err(e)
+            return Err(e);
         }
     }
+
+    if !found {
+        let e = io::Error::new(io::ErrorKind::NotFound, "No quarantine directory found.");
+        eprintln!("❌ Failed to restore quarantined files: {}", e);
+        return Err(e);
+    }
+
+    if dry_run {
+        return output::print_report(format, &report);
+    }
+
+    let failed = report.failed_moves().count();
+    if failed > 0 {
+        eprintln!(
+            "⚠️ {} files could not be restored and stay quarantined.",
+            failed
+        );
+        return output::print_report(format, &report);
+    }
+
+    if format != OutputFormat::Text {
+        return output::print_report(format, &report);
+    }
+    println!("✅ Quarantined files restored.");
+    Ok(())
 }
@@ -1,20 +1,25 @@
+use crate::options::ScanOptions;
 use crate::scanner;
 use std::io;
-use std::path::Path;
 
-pub fn run_scan(dir: &Path) -> io::Result<()> {
-    match scanner::scan_directory(dir) {
-        Ok(files) => {
-            if files.is_empty() {
-                println!("❌ No files found in the specified directory.");
-            } else {
-                println!("📂 Found {} files:", files.len());
-                for file in files {
-                    println!("{}", file.display());
-                }
-            }
-            Ok(())
+pub fn run_scan(options: &ScanOptions) -> io::Result<()> {
+    let scanned = scanner::scan_directories(&options.roots, &options.ignore, &options.walk)?;
+    if scanned.files.is_empty() {
+        println!("❌ No files found in the specified directory.");
+        return Ok(());
+    }
+
+    println!("📂 Found {} files:", scanned.files.len());
+    for file in &scanned.files {
+        println!("{}", file.display());
+    }
+
+    for paths in &scanned.hardlinks {
+        println!("\n🔗 Hardlinks to one file (counted once):");
+        for path in paths {
+            println!("    {}", path.display());
         }
-        Err(e) => Err(e),
     }
+
+    Ok(())
 }
@@ -0,0 +1,48 @@
+use crate::options::{ScanMode, ScanOptions};
+use crate::prompts;
+use crate::quarantine::{self, QuarantineLocation};
+use crate::report::Report;
+use crate::tui::{self, Outcome};
+use std::io::{self, IsTerminal};
+
+pub fn run_tui(
+    mut options: ScanOptions,
+    location: &QuarantineLocation,
+    mode: Option<ScanMode>,
+    yes: bool,
+) -> io::Result<()> {
+    // Check before the scan, which can take a long time.
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other("tui needs an interactive terminal"));
+    }
+
+    options.mode = prompts::resolve_scan_mode(mode, yes)?;
+
+    let result = super::find_duplicates(&options)?;
+    if result.is_empty() {
+        println!("✅ No duplicate or similar files found.");
+        return Ok(());
+    }
+
+    match tui::run(&result)? {
+        Outcome::Quarantine(files) if !files.is_empty() => {
+            let mut report = Report::new();
+            let result =
+                quarantine::quarantine_by_root(files, &options.roots, location, &mut report);
+            super::register_quarantines(&quarantine::quarantine_dirs(&options.roots, location));
+            let failed = report.failed_moves().count();
+            println!("✅ Quarantined {} files.", report.moves.len() - failed);
+            if failed > 0 {
+                println!("⚠️ {} files could not be moved.", failed);
+            }
+            if let Err(e) = &result {
+                eprintln!("❌ Failed to quarantine files: {}", e);
+            }
+            result
+        }
+        _ => {
+            println!("No files were changed.");
+            Ok(())
+        }
+    }
+}
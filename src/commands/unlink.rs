@@ -0,0 +1,39 @@
+use crate::config::{load_link_log, write_link_log};
+use crate::linker;
+use std::io;
+
+pub fn run_unlink() -> io::Result<()> {
+    let records = load_link_log();
+    if records.is_empty() {
+        println!("✅ No links recorded, nothing to undo.");
+        return Ok(());
+    }
+
+    let mut failed = Vec::new();
+    let mut restored = 0;
+
+    for record in records {
+        match linker::undo_link(&record) {
+            Ok(()) => restored += 1,
+            Err(e) => {
+                eprintln!("❌ Failed to undo link {}: {}", record.path.display(), e);
+                failed.push(record);
+            }
+        }
+    }
+
+    write_link_log(&failed)?;
+    println!(
+        "✅ Restored {} linked file(s) as independent copies.",
+        restored
+    );
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} link(s) could not be undone and stay in the link log",
+            failed.len()
+        )))
+    }
+}
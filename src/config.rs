@@ -1,11 +1,32 @@
 use dirs;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
+use crate::quarantine::QuarantineLocation;
+use crate::report::LinkRecord;
+use crate::session::Session;
+
 const CONFIG_DIR_NAME: &str = ".deduck";
 const LAST_DIR_FILE: &str = "last_dir.txt";
-const SCAN_MODE_FILE: &str = "scan_mode.txt";
+const SESSION_FILE: &str = "session.json";
+const LINK_LOG_FILE: &str = "links.json";
+const HASH_CACHE_FILE: &str = "hash_cache.json";
+const SETTINGS_FILE: &str = "config.json";
+const QUARANTINES_FILE: &str = "quarantines.json";
+
+/// User settings read from `~/.deduck/config.json`. Command-line flags take
+/// precedence.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    /// Store directory for quarantined files instead of each scan root.
+    #[serde(default)]
+    pub quarantine_dir: Option<PathBuf>,
+    /// Quarantine into the central store under the user's data directory.
+    #[serde(default)]
+    pub central_quarantine: bool,
+}
 
 fn config_dir() -> Option<PathBuf> {
     let home_dir = dirs::home_dir()?;
@@ -17,7 +38,7 @@ fn config_path(filename: &str) -> Option<PathBuf> {
     Some(config_dir.join(filename))
 }
 
-pub fn save_last_dir(dir: &Path) -> io::Result<()> {
+pub fn save_last_dirs(dirs: &[PathBuf]) -> io::Result<()> {
     let config_file = config_path(LAST_DIR_FILE);
 
     if let Some(path) = config_file {
@@ -25,55 +46,100 @@ pub fn save_last_dir(dir: &Path) -> io::Result<()> {
         fs::create_dir_all(parent_dir)?;
 
         let mut file = fs::File::create(path)?;
-        writeln!(file, "{}", dir.display())?;
+        for dir in dirs {
+            writeln!(file, "{}", dir.display())?;
+        }
     }
 
     Ok(())
 }
 
-pub fn load_last_dir() -> Option<PathBuf> {
-    let path = config_path(LAST_DIR_FILE)?;
+/// Directories saved by the last run that specified `--dir`, one per line.
+pub fn load_last_dirs() -> Vec<PathBuf> {
+    let Some(path) = config_path(LAST_DIR_FILE) else {
+        return Vec::new();
+    };
+
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub fn save_session(session: &Session) -> io::Result<()> {
+    if let Some(path) = config_path(SESSION_FILE) {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)?;
+
+        let content = serde_json::to_string(session).map_err(io::Error::other)?;
+        fs::write(path, content)?;
+    }
+
+    Ok(())
+}
 
+pub fn load_session() -> Option<Session> {
+    let path = config_path(SESSION_FILE)?;
     let content = fs::read_to_string(path).ok()?;
-    let trimmed = content.trim();
+    serde_json::from_str(&content).ok()
+}
 
-    if trimmed.is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(trimmed))
+pub fn clear_session() -> io::Result<()> {
+    match config_path(SESSION_FILE) {
+        Some(path) if path.exists() => fs::remove_file(path),
+        _ => Ok(()),
     }
 }
 
-pub fn save_scan_mode(mode: usize) -> io::Result<()> {
-    let path = config_path(SCAN_MODE_FILE);
+/// Appends `records` to the log of links created by deduck, so they can be
+/// undone later.
+pub fn save_link_log(records: &[LinkRecord]) -> io::Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let mut log = load_link_log();
+    log.extend_from_slice(records);
+    write_link_log(&log)
+}
 
-    if let Some(path) = path {
+pub fn load_link_log() -> Vec<LinkRecord> {
+    config_path(LINK_LOG_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_link_log(records: &[LinkRecord]) -> io::Result<()> {
+    if let Some(path) = config_path(LINK_LOG_FILE) {
         let parent = path.parent().unwrap_or(Path::new("."));
         fs::create_dir_all(parent)?;
 
-        let mut file = fs::File::create(path)?;
-        writeln!(file, "{}", mode)?;
+        let content = serde_json::to_string_pretty(records).map_err(io::Error::other)?;
+        fs::write(path, content)?;
     }
 
     Ok(())
 }
 
-pub fn load_scan_mode() -> Option<usize> {
-    let path = config_path(SCAN_MODE_FILE)?;
-    let content = fs::read_to_string(path).ok()?;
-    content.trim().parse::<usize>().ok()
+pub fn hash_cache_path() -> Option<PathBuf> {
+    config_path(HASH_CACHE_FILE)
 }
 
-pub fn get_dir_or_saved(dir_opt: &Option<PathBuf>) -> PathBuf {
-    if let Some(dir) = dir_opt {
-        if let Err(err) = save_last_dir(dir) {
+pub fn get_dirs_or_saved(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    if !dirs.is_empty() {
+        if let Err(err) = save_last_dirs(dirs) {
             eprintln!("Warning: failed to save last directory: {}", err);
         }
-        return dir.clone();
+        return dirs.to_vec();
     }
 
-    if let Some(saved_dir) = load_last_dir() {
-        return saved_dir;
+    let saved_dirs = load_last_dirs();
+    if !saved_dirs.is_empty() {
+        return saved_dirs;
     }
 
     eprintln!(
@@ -81,3 +147,73 @@ pub fn get_dir_or_saved(dir_opt: &Option<PathBuf>) -> PathBuf {
     );
     std::process::exit(1);
 }
+
+pub fn load_settings() -> Settings {
+    config_path(SETTINGS_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// The central quarantine store, holding quarantines from any number of
+/// roots under the user's data directory.
+pub fn central_quarantine_dir() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("deduck").join("quarantine"))
+}
+
+/// Where this run quarantines files: `dir` or the central store when given
+/// on the command line, else whatever the settings file chooses, else inside
+/// each scan root.
+pub fn quarantine_location(dir: Option<&Path>, central: bool) -> QuarantineLocation {
+    let settings = load_settings();
+    let store = match dir {
+        Some(dir) => Some(dir.to_path_buf()),
+        None if central => central_quarantine_dir(),
+        None => settings.quarantine_dir.or_else(|| {
+            settings
+                .central_quarantine
+                .then(central_quarantine_dir)
+                .flatten()
+        }),
+    };
+
+    store.map_or(QuarantineLocation::InRoot, QuarantineLocation::Store)
+}
+
+/// Remembers quarantine folders so `deduck quarantine list` can find them
+/// wherever they are.
+pub fn register_quarantine_dirs(dirs: &[PathBuf]) -> io::Result<()> {
+    let mut known = load_quarantine_dirs();
+    let mut changed = false;
+    for dir in dirs {
+        let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.clone());
+        if !known.contains(&dir) {
+            known.push(dir);
+            changed = true;
+        }
+    }
+
+    if changed {
+        write_quarantine_dirs(&known)?;
+    }
+    Ok(())
+}
+
+pub fn load_quarantine_dirs() -> Vec<PathBuf> {
+    config_path(QUARANTINES_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_quarantine_dirs(dirs: &[PathBuf]) -> io::Result<()> {
+    if let Some(path) = config_path(QUARANTINES_FILE) {
+        let parent = path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)?;
+
+        let content = serde_json::to_string_pretty(dirs).map_err(io::Error::other)?;
+        fs::write(path, content)?;
+    }
+
+    Ok(())
+}
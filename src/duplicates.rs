@@ -1,133 +1,305 @@
-use indicatif::{ProgressBar, ProgressStyle};
-use std::collections::HashMap;
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
 use crate::{
+    cache::HashCache,
     filters,
-    hasher::{HashAlgorithm, hash_files},
-    quarantine, scanner,
-    similar::similar_images,
+    hasher::hash_files_staged_cached,
+    options::{ScanMode, ScanOptions},
+    policy::KeepPolicy,
+    scanner,
+    similar::{SIMILARITY_EXTS, similar_images},
 };
 
-pub fn duplicates(dir: &Path, scan_choice: usize, quarantine_flag: bool) -> io::Result<usize> {
-    let files = scanner::scan_directory(dir)?;
-    let files_found = files.len();
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch.
+    pub mtime: Option<u64>,
+    /// Set by the keep policy for files that must never be acted on.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
+}
 
-    if files.is_empty() {
-        println!("❌ No files found in the directory.");
-        return Ok(0);
+impl FileEntry {
+    pub fn new(path: PathBuf) -> Self {
+        let metadata = fs::metadata(&path).ok();
+        let mtime = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        FileEntry {
+            size: metadata.map_or(0, |m| m.len()),
+            path,
+            mtime,
+            protected: false,
+        }
     }
+}
 
-    let doc_exts = ["pdf", "txt", "doc", "xlsx"];
-    let image_exts = ["png", "jpg", "jpeg"];
-
-    let (doc_files, image_files, all_files): (Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>) =
-        if scan_choice == 2 {
-            let mut docs = Vec::new();
-            let mut images = Vec::new();
-
-            for file in &files {
-                if let Some(ext) = file.extension().and_then(|e| e.to_str()) {
-                    let ext = ext.to_lowercase();
-                    if doc_exts.contains(&ext.as_str()) {
-                        docs.push(file.clone());
-                    } else if image_exts.contains(&ext.as_str()) {
-                        images.push(file.clone());
-                    }
-                }
-            }
+/// Files whose full content hashes are identical.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub algorithm: String,
+    pub size: u64,
+    pub files: Vec<FileEntry>,
+}
 
-            let all = docs.iter().chain(images.iter()).cloned().collect();
-            (docs, images, all)
-        } else {
-            let allowed_exts = ["pdf", "txt", "doc", "xlsx", "png", "jpeg", "jpg"];
-            let batches = filters::batch(files, &allowed_exts)?;
+impl DuplicateGroup {
+    pub fn new(hash: String, algorithm: &str, mut paths: Vec<PathBuf>) -> Self {
+        paths.sort();
+        let size = paths
+            .first()
+            .and_then(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .unwrap_or(0);
 
-            if batches.is_empty() {
-                println!("⚠️ No files matching allowed extensions found.");
-                return Ok(files_found);
-            }
+        DuplicateGroup {
+            hash,
+            algorithm: algorithm.to_string(),
+            size,
+            files: paths.into_iter().map(FileEntry::new).collect(),
+        }
+    }
+
+    /// Copies that may be removed or replaced: every file but the kept one
+    /// (`files[0]`), minus protected files.
+    pub fn redundant(&self) -> impl Iterator<Item = &FileEntry> {
+        self.files.iter().skip(1).filter(|f| !f.protected)
+    }
+
+    /// Bytes freed by removing every redundant copy.
+    pub fn reclaimable(&self) -> u64 {
+        self.size * self.redundant().count() as u64
+    }
+}
+
+/// Images that look alike according to their perceptual hashes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarGroup {
+    pub base: FileEntry,
+    pub similar: Vec<FileEntry>,
+}
+
+/// Paths that are hardlinks to one file. They share their content on disk,
+/// so they are counted once and never reported as duplicates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardlinkGroup {
+    pub size: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// Everything a scan found, in a stable order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub files_found: usize,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub similar: Vec<SimilarGroup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hardlinks: Vec<HardlinkGroup>,
+}
+
+impl ScanResult {
+    /// Every file that is not the kept copy of its group.
+    pub fn redundant_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        for group in &self.similar {
+            files.extend(
+                group
+                    .similar
+                    .iter()
+                    .filter(|f| !f.protected)
+                    .map(|f| f.path.clone()),
+            );
+        }
+        for group in &self.duplicates {
+            files.extend(group.redundant().map(|f| f.path.clone()));
+        }
+        files
+    }
+
+    /// Every file that survives when the redundant files are removed: the
+    /// kept copy of each group and protected copies.
+    pub fn kept_files(&self) -> HashSet<PathBuf> {
+        let mut files = HashSet::new();
+        for group in &self.similar {
+            files.insert(group.base.path.clone());
+            files.extend(
+                group
+                    .similar
+                    .iter()
+                    .filter(|f| f.protected)
+                    .map(|f| f.path.clone()),
+            );
+        }
+        for group in &self.duplicates {
+            files.extend(
+                group
+                    .files
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, f)| *i == 0 || f.protected)
+                    .map(|(_, f)| f.path.clone()),
+            );
+        }
+        files
+    }
 
-            let flat: Vec<PathBuf> = batches.values().flat_map(|v| v.clone()).collect();
-            (flat.clone(), vec![], flat)
-        };
+    /// Keeps only groups pairing at least one reference copy with at least
+    /// one copy outside the reference directories.
+    pub fn retain_reference_groups<F>(&mut self, is_reference: F)
+    where
+        F: Fn(&Path) -> bool,
+    {
+        self.duplicates
+            .retain(|group| pairs_reference(group.files.iter(), &is_reference));
+        self.similar.retain(|group| {
+            let files = std::iter::once(&group.base).chain(&group.similar);
+            pairs_reference(files, &is_reference)
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.duplicates.is_empty() && self.similar.is_empty()
+    }
+}
+
+fn pairs_reference<'a, F>(files: impl Iterator<Item = &'a FileEntry>, is_reference: &F) -> bool
+where
+    F: Fn(&Path) -> bool,
+{
+    let (references, others): (Vec<_>, Vec<_>) = files.partition(|f| is_reference(&f.path));
+    !references.is_empty() && !others.is_empty()
+}
+
+pub fn find_duplicates(options: &ScanOptions) -> io::Result<ScanResult> {
+    find_duplicates_with_progress(options, &ProgressBar::hidden())
+}
+
+/// Same as [`find_duplicates`], reporting hashing progress on `pb`.
+pub fn find_duplicates_with_progress(
+    options: &ScanOptions,
+    pb: &ProgressBar,
+) -> io::Result<ScanResult> {
+    let mut roots = options.roots.clone();
+    roots.extend(options.references.iter().cloned());
 
-    let algo = match scan_choice {
-        0 => HashAlgorithm::XxHash,
-        1 => HashAlgorithm::Blake3,
-        2 => HashAlgorithm::Sha256,
-        _ => unreachable!(),
+    let scanned = scanner::scan_directories(&roots, &options.ignore, &options.walk)?;
+    let files = scanned.files;
+
+    // Cached hashes of files under the roots that this scan no longer finds
+    // are dropped when the cache is saved.
+    let found: HashSet<PathBuf> = match options.cache_file {
+        Some(_) => files
+            .iter()
+            .chain(scanned.hardlinks.iter().flatten())
+            .cloned()
+            .collect(),
+        None => HashSet::new(),
     };
 
-    let pb = ProgressBar::new(if scan_choice == 2 {
-        doc_files.len()
-    } else {
-        all_files.len()
-    } as u64);
-
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files")
-            .unwrap()
-            .progress_chars("=> "),
-    );
-    pb.set_message("🔍 Hashing files...");
-
-    let hash_map: HashMap<String, Vec<PathBuf>> = if scan_choice == 2 {
-        hash_files(doc_files.clone(), HashAlgorithm::Sha256, pb.clone())
-    } else {
-        hash_files(all_files.clone(), algo, pb.clone())
+    let mut result = ScanResult {
+        files_found: scanned.paths_found,
+        hardlinks: scanned
+            .hardlinks
+            .into_iter()
+            .map(|files| HardlinkGroup {
+                size: fs::metadata(&files[0]).map_or(0, |m| m.len()),
+                files,
+            })
+            .collect(),
+        ..ScanResult::default()
     };
 
-    pb.finish_with_message("✅ Finished hashing files");
+    let ext_filter = &options.ext_filter;
+    let size_filter = &options.size_filter;
+    let deep = options.mode == ScanMode::Deep;
 
-    let mut duplicates_found = false;
-    let mut similar_found = false;
-    let mut to_quarantine: Vec<PathBuf> = Vec::new();
+    let (batches, image_files) = if deep {
+        let (images, others): (Vec<PathBuf>, Vec<PathBuf>) = files
+            .into_iter()
+            .filter(|file| ext_filter.allows(file))
+            .partition(|file| SIMILARITY_EXTS.contains(&filters::extension_of(file).as_str()));
+        let images = images
+            .into_iter()
+            .filter(|file| size_filter.allows_path(file))
+            .collect();
 
-    if scan_choice == 2 {
-        println!("🔍 Performing image similarity scan...");
-        let similar_map = similar_images(image_files.clone(), 10);
+        (filters::batch(others, ext_filter, size_filter)?, images)
+    } else {
+        (filters::batch(files, ext_filter, size_filter)?, vec![])
+    };
 
-        for (base, similars) in &similar_map {
-            if !similars.is_empty() {
-                similar_found = true;
-                println!("\n🖼️ Visually similar images:");
-                println!("   Base: {}", base.display());
-                for path in similars {
-                    println!("   ↳ {}", path.display());
-                }
-
-                let mut similar_group = vec![base.clone()];
-                similar_group.extend(similars.clone());
-                to_quarantine.extend(similar_group.into_iter().skip(1));
-            }
-        }
+    let algo = options.mode.algorithm();
+    let algo_name = algo.name();
+
+    // Only files sharing their size with another file can be duplicates,
+    // whatever their extension.
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for ((_, size), group) in batches {
+        by_size.entry(size).or_default().extend(group);
     }
 
-    for (hash, paths) in &hash_map {
-        if paths.len() > 1 {
-            duplicates_found = true;
-            println!("\n🔁 Duplicate Hash: {}", hash);
-            for path in paths {
-                println!("    {}", path.display());
-            }
+    let candidates: Vec<PathBuf> = by_size
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .flatten()
+        .collect();
+
+    let cache = match &options.cache_file {
+        Some(path) => HashCache::load(path),
+        None => HashCache::default(),
+    };
 
-            to_quarantine.extend(paths.iter().skip(1).cloned());
+    pb.set_length(candidates.len() as u64);
+    let hash_map = hash_files_staged_cached(candidates, algo, pb.clone(), &cache);
+
+    if let Some(path) = &options.cache_file {
+        cache.prune(&roots, &found);
+        if let Err(e) = cache.save(path) {
+            eprintln!("⚠️ Failed to save hash cache: {}", e);
         }
     }
 
-    if quarantine_flag && !to_quarantine.is_empty() {
-        let quarantine_dir = quarantine::get_quarantine_dir(dir);
-        if let Err(e) = quarantine::quarantine_duplicates(to_quarantine, &quarantine_dir) {
-            eprintln!("❌ Failed to quarantine files: {}", e);
+    result.duplicates = hash_map
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(hash, paths)| DuplicateGroup::new(hash, algo_name, paths))
+        .collect();
+    result
+        .duplicates
+        .sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.hash.cmp(&b.hash)));
+
+    if deep && !image_files.is_empty() {
+        pb.set_message("🔍 Performing image similarity scan...");
+        let similar_map = similar_images(image_files, options.similarity_threshold);
+
+        for (base, similars) in similar_map {
+            if !similars.is_empty() {
+                result.similar.push(SimilarGroup {
+                    base: FileEntry::new(base),
+                    similar: similars.into_iter().map(FileEntry::new).collect(),
+                });
+            }
         }
+        result.similar.sort_by(|a, b| a.base.path.cmp(&b.base.path));
     }
 
-    if !duplicates_found && !similar_found {
-        println!("✅ No duplicate or similar files found.");
+    let policy = options.keep_policy.with_references(&options.references);
+    policy.apply_all(&mut result);
+
+    if !options.references.is_empty() {
+        let references = KeepPolicy::default().with_references(&options.references);
+        result.retain_reference_groups(|path| references.is_protected(path));
     }
 
-    Ok(files_found)
+    Ok(result)
 }
@@ -1,21 +1,170 @@
 use std::collections::HashMap;
 use std::fs::{self};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+pub const PRESETS: &[(&str, &[&str])] = &[
+    (
+        "documents",
+        &[
+            "pdf", "txt", "doc", "docx", "odt", "rtf", "md", "xls", "xlsx", "ods", "csv", "ppt",
+            "pptx", "odp", "epub",
+        ],
+    ),
+    (
+        "images",
+        &[
+            "png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff", "heic", "svg", "raw", "cr2",
+            "nef",
+        ],
+    ),
+    (
+        "video",
+        &[
+            "mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "m4v", "mpg", "mpeg", "3gp",
+        ],
+    ),
+    (
+        "audio",
+        &["mp3", "flac", "wav", "aac", "ogg", "m4a", "wma", "opus"],
+    ),
+    (
+        "archives",
+        &[
+            "zip", "tar", "gz", "tgz", "bz2", "xz", "7z", "rar", "zst", "iso", "dmg",
+        ],
+    ),
+];
+
+pub fn preset_extensions(name: &str) -> Option<&'static [&'static str]> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| *preset == name)
+        .map(|(_, exts)| *exts)
+}
+
+/// Decides which files take part in a scan by extension. An empty include
+/// list means every file is included, and excludes always win.
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl ExtensionFilter {
+    pub fn new(include: &[String], exclude: &[String], presets: &[String]) -> Self {
+        let mut filter = ExtensionFilter {
+            include: include.iter().map(|e| normalize_ext(e)).collect(),
+            exclude: exclude.iter().map(|e| normalize_ext(e)).collect(),
+        };
+
+        for preset in presets {
+            if let Some(exts) = preset_extensions(preset) {
+                filter.include.extend(exts.iter().map(|e| e.to_string()));
+            }
+        }
+
+        filter
+    }
+
+    pub fn include(exts: &[&str]) -> Self {
+        ExtensionFilter {
+            include: exts.iter().map(|e| normalize_ext(e)).collect(),
+            exclude: Vec::new(),
+        }
+    }
+
+    pub fn allows(&self, path: &Path) -> bool {
+        let ext = extension_of(path);
+
+        if self.exclude.contains(&ext) {
+            return false;
+        }
+
+        self.include.is_empty() || self.include.contains(&ext)
+    }
+}
+
+/// Decides which files take part in a scan by size. Empty files are left out
+/// unless `include_empty` is set, since they all hash the same.
+#[derive(Debug, Clone, Default)]
+pub struct SizeFilter {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub include_empty: bool,
+}
+
+impl SizeFilter {
+    pub fn allows(&self, size: u64) -> bool {
+        if size == 0 && !self.include_empty {
+            return false;
+        }
+
+        self.min.is_none_or(|min| size >= min) && self.max.is_none_or(|max| size <= max)
+    }
+
+    pub fn allows_path(&self, path: &Path) -> bool {
+        fs::metadata(path).is_ok_and(|m| self.allows(m.len()))
+    }
+}
+
+/// Parses a size such as `512`, `10K`, `1.5M` or `2GiB`. Units are powers of
+/// 1024 and case-insensitive.
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("invalid size `{}`", input))?;
+
+    let exponent = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 0,
+        "k" | "kb" | "kib" => 1,
+        "m" | "mb" | "mib" => 2,
+        "g" | "gb" | "gib" => 3,
+        "t" | "tb" | "tib" => 4,
+        _ => {
+            return Err(format!(
+                "invalid size unit in `{}` (expected B, K, M, G or T)",
+                input
+            ));
+        }
+    };
+
+    Ok((number * 1024f64.powi(exponent)).round() as u64)
+}
+
+/// Lowercased extension of `path`, or an empty string if it has none.
+pub fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn normalize_ext(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_lowercase()
+}
 
 pub fn batch(
     files: Vec<PathBuf>,
-    allowed_exts: &[&str],
+    filter: &ExtensionFilter,
+    size_filter: &SizeFilter,
 ) -> std::io::Result<HashMap<(String, u64), Vec<PathBuf>>> {
     let mut batches: HashMap<(String, u64), Vec<PathBuf>> = HashMap::new();
 
     for file in files {
-        if let Some(ext) = file.extension().and_then(|e| e.to_str()) {
-            if allowed_exts.contains(&ext) {
-                let metadata = fs::metadata(&file)?;
-                let size = metadata.len();
-                let key: (String, u64) = (ext.to_string(), size);
-                batches.entry(key).or_default().push(file);
+        if filter.allows(&file) {
+            let metadata = fs::metadata(&file)?;
+            let size = metadata.len();
+            if !size_filter.allows(size) {
+                continue;
             }
+            let key: (String, u64) = (extension_of(&file), size);
+            batches.entry(key).or_default().push(file);
         }
     }
 
@@ -5,10 +5,15 @@ use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::fs::File;
 use std::hash::Hasher;
-use std::io::{BufReader, Read};
-use std::path::PathBuf;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use twox_hash::XxHash64;
 
+use crate::cache::HashCache;
+
+/// Bytes read from each end of a file for the partial hash.
+pub const PARTIAL_HASH_BYTES: u64 = 16 * 1024;
+
 #[derive(Debug, Clone)]
 pub enum HashAlgorithm {
     Sha256,
@@ -16,19 +21,67 @@ pub enum HashAlgorithm {
     XxHash,
 }
 
-pub fn hash_file(path: &PathBuf, algo: &HashAlgorithm) -> std::io::Result<String> {
+impl HashAlgorithm {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sha256" => Some(HashAlgorithm::Sha256),
+            "blake3" => Some(HashAlgorithm::Blake3),
+            "xxhash64" => Some(HashAlgorithm::XxHash),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::XxHash => "xxhash64",
+        }
+    }
+}
+
+pub fn hash_file(path: &Path, algo: &HashAlgorithm) -> std::io::Result<String> {
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
+    hash_reader(&mut reader, algo)
+}
 
+/// Hashes the file length together with its first and last `chunk` bytes.
+/// Files no larger than two chunks are hashed in full.
+pub fn hash_file_partial(
+    path: &PathBuf,
+    algo: &HashAlgorithm,
+    chunk: u64,
+) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    let mut buffer = len.to_le_bytes().to_vec();
+    if len <= chunk * 2 {
+        file.read_to_end(&mut buffer)?;
+    } else {
+        let mut head = vec![0u8; chunk as usize];
+        file.read_exact(&mut head)?;
+        file.seek(SeekFrom::End(-(chunk as i64)))?;
+        let mut tail = vec![0u8; chunk as usize];
+        file.read_exact(&mut tail)?;
+        buffer.extend(head);
+        buffer.extend(tail);
+    }
+
+    hash_reader(&mut buffer.as_slice(), algo)
+}
+
+fn hash_reader<R: Read>(reader: &mut R, algo: &HashAlgorithm) -> std::io::Result<String> {
     if let HashAlgorithm::Sha256 = algo {
         let mut hasher = Sha256::new();
-        std::io::copy(&mut reader, &mut hasher)?;
+        std::io::copy(reader, &mut hasher)?;
         return Ok(format!("{:x}", hasher.finalize()));
     }
 
     if let HashAlgorithm::Blake3 = algo {
         let mut hasher = blake3::Hasher::new();
-        hasher.update_reader(&mut reader)?;
+        hasher.update_reader(reader)?;
         return Ok(hasher.finalize().to_hex().to_string());
     }
 
@@ -45,10 +98,7 @@ pub fn hash_file(path: &PathBuf, algo: &HashAlgorithm) -> std::io::Result<String
         return Ok(format!("{:x}", hasher.finish()));
     }
 
-    Err(std::io::Error::new(
-        std::io::ErrorKind::Other,
-        "Unsupported hash algorithm",
-    ))
+    Err(std::io::Error::other("Unsupported hash algorithm"))
 }
 
 pub fn hash_files(
@@ -56,10 +106,65 @@ pub fn hash_files(
     algo: HashAlgorithm,
     pb: ProgressBar,
 ) -> HashMap<String, Vec<PathBuf>> {
+    group_by_hash(files, pb, |file| hash_file(file, &algo))
+}
+
+pub fn hash_files_partial(
+    files: Vec<PathBuf>,
+    algo: HashAlgorithm,
+    pb: ProgressBar,
+) -> HashMap<String, Vec<PathBuf>> {
+    group_by_hash(files, pb, |file| {
+        hash_file_partial(file, &algo, PARTIAL_HASH_BYTES)
+    })
+}
+
+/// Partially hashes `files`, then fully hashes only those whose partial
+/// hashes collide. Files with a unique partial hash are left out of the
+/// result since they cannot have a duplicate among `files`.
+pub fn hash_files_staged(
+    files: Vec<PathBuf>,
+    algo: HashAlgorithm,
+    pb: ProgressBar,
+) -> HashMap<String, Vec<PathBuf>> {
+    hash_files_staged_cached(files, algo, pb, &HashCache::default())
+}
+
+/// Same as [`hash_files_staged`], reusing hashes from `cache` for files that
+/// have not changed and recording the ones it computes.
+pub fn hash_files_staged_cached(
+    files: Vec<PathBuf>,
+    algo: HashAlgorithm,
+    pb: ProgressBar,
+    cache: &HashCache,
+) -> HashMap<String, Vec<PathBuf>> {
+    let partial_key = format!("{}:partial", algo.name());
+    let partial = group_by_hash(files, pb.clone(), |file| {
+        cache.get_or_compute(file, &partial_key, || {
+            hash_file_partial(file, &algo, PARTIAL_HASH_BYTES)
+        })
+    });
+
+    let collisions: Vec<PathBuf> = partial
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .flatten()
+        .collect();
+
+    pb.inc_length(collisions.len() as u64);
+    group_by_hash(collisions, pb, |file| {
+        cache.get_or_compute(file, algo.name(), || hash_file(file, &algo))
+    })
+}
+
+fn group_by_hash<F>(files: Vec<PathBuf>, pb: ProgressBar, hash: F) -> HashMap<String, Vec<PathBuf>>
+where
+    F: Fn(&PathBuf) -> std::io::Result<String> + Sync,
+{
     let maps: Vec<HashMap<String, Vec<PathBuf>>> = files
         .par_iter()
         .progress_with(pb)
-        .filter_map(|file| match hash_file(file, &algo) {
+        .filter_map(|file| match hash(file) {
             Ok(hash) => Some((hash, file.clone())),
             Err(_) => None,
         })
@@ -1,11 +1,22 @@
+pub mod cache;
 pub mod commands;
 pub mod config;
 pub mod duplicates;
 pub mod filters;
 pub mod hasher;
+pub mod linker;
+pub mod mounts;
+pub mod mover;
+pub mod options;
+pub mod output;
+pub mod policy;
 pub mod prompts;
 pub mod quarantine;
+pub mod reflink;
 pub mod report;
+pub mod review;
 pub mod scanner;
+pub mod session;
 pub mod similar;
+pub mod tui;
 pub mod utils;
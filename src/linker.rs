@@ -0,0 +1,254 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+use crate::duplicates::DuplicateGroup;
+use crate::report::{LinkKind, LinkRecord, Report};
+
+/// Replaces every redundant copy in `groups` with a hardlink to the kept
+/// file (`files[0]`). Each copy is compared byte-for-byte with the kept file
+/// first, and copies on a different filesystem are skipped. In a dry run the
+/// checks still happen but only the report is filled in.
+pub fn hardlink_duplicates(groups: &[DuplicateGroup], report: &mut Report) -> io::Result<()> {
+    for group in groups {
+        let Some(keep) = group.files.first() else {
+            continue;
+        };
+
+        for file in group.redundant() {
+            if let Err(e) = hardlink_file(&keep.path, &file.path, group.size, report) {
+                eprintln!("❌ Failed to link {}: {}", file.path.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn hardlink_file(keep: &Path, path: &Path, size: u64, report: &mut Report) -> io::Result<()> {
+    if !same_device(keep, path)? {
+        eprintln!(
+            "⚠️ Skipping {}: not on the same filesystem as {}",
+            path.display(),
+            keep.display()
+        );
+        return Ok(());
+    }
+
+    if same_inode(keep, path)? {
+        return Ok(());
+    }
+
+    if !files_identical(keep, path)? {
+        eprintln!(
+            "⚠️ Skipping {}: content differs from {}",
+            path.display(),
+            keep.display()
+        );
+        return Ok(());
+    }
+
+    if !report.dry_run {
+        // Link under a temporary name, then rename over the copy so the path
+        // is never missing.
+        let temp = temp_sibling(path);
+        fs::hard_link(keep, &temp)?;
+        if let Err(e) = fs::rename(&temp, path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    }
+
+    report.add_link(LinkRecord {
+        path: canonical(path),
+        target: canonical(keep),
+        kind: LinkKind::Hardlink,
+        size,
+    });
+
+    Ok(())
+}
+
+/// Replaces every redundant copy in `groups` with a symlink to the kept file
+/// (`files[0]`), relative to the copy's directory where possible. Each copy is
+/// compared byte-for-byte with the kept file first.
+pub fn symlink_duplicates(groups: &[DuplicateGroup], report: &mut Report) -> io::Result<()> {
+    for group in groups {
+        let Some(keep) = group.files.first() else {
+            continue;
+        };
+
+        for file in group.redundant() {
+            if let Err(e) = symlink_file(&keep.path, &file.path, group.size, report) {
+                eprintln!("❌ Failed to symlink {}: {}", file.path.display(), e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn symlink_file(keep: &Path, path: &Path, size: u64, report: &mut Report) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.file_type().is_symlink() {
+        return Ok(());
+    }
+
+    if !files_identical(keep, path)? {
+        eprintln!(
+            "⚠️ Skipping {}: content differs from {}",
+            path.display(),
+            keep.display()
+        );
+        return Ok(());
+    }
+
+    let keep = fs::canonicalize(keep)?;
+    let path = fs::canonicalize(path)?;
+    let link_dir = path.parent().unwrap_or(Path::new("/"));
+    let link_target = relative_path(link_dir, &keep);
+
+    if !report.dry_run {
+        let temp = temp_sibling(&path);
+        create_symlink(&link_target, &temp)?;
+        if let Err(e) = fs::rename(&temp, &path) {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    }
+
+    report.add_link(LinkRecord {
+        path,
+        target: keep,
+        kind: LinkKind::Symlink,
+        size,
+    });
+
+    Ok(())
+}
+
+/// Turns a link created by deduck back into an independent copy of its
+/// target. A path is only replaced while it is still the recorded link, so
+/// edits made since are never overwritten. Reflinked copies are already
+/// independent and are left alone.
+pub fn undo_link(record: &LinkRecord) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(&record.path)?;
+
+    let still_linked = match record.kind {
+        LinkKind::Reflink => return Ok(()),
+        LinkKind::Symlink => {
+            metadata.file_type().is_symlink()
+                && fs::canonicalize(&record.path)? == fs::canonicalize(&record.target)?
+        }
+        LinkKind::Hardlink => {
+            metadata.file_type().is_file() && same_inode(&record.path, &record.target)?
+        }
+    };
+    if !still_linked {
+        return Err(io::Error::other(
+            "path no longer links to the recorded target",
+        ));
+    }
+
+    let temp = temp_sibling(&record.path);
+    fs::copy(&record.target, &temp)?;
+    if let Err(e) = fs::rename(&temp, &record.path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Path of `to` relative to the directory `from_dir`. Both should be
+/// absolute; if they share no root, `to` is returned unchanged.
+pub fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from_dir.components().collect();
+    let target: Vec<Component> = to.components().collect();
+
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return to.to_path_buf();
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..from.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    relative
+}
+
+pub fn files_identical(a: &Path, b: &Path) -> io::Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+
+    let mut reader_a = BufReader::new(File::open(a)?);
+    let mut reader_b = BufReader::new(File::open(b)?);
+    let mut buf_a = [0u8; 8192];
+    let mut buf_b = [0u8; 8192];
+
+    loop {
+        let count = reader_a.read(&mut buf_a)?;
+        if count == 0 {
+            let mut rest = [0u8; 1];
+            return Ok(reader_b.read(&mut rest)? == 0);
+        }
+        reader_b.read_exact(&mut buf_b[..count])?;
+        if buf_a[..count] != buf_b[..count] {
+            return Ok(false);
+        }
+    }
+}
+
+/// The absolute, symlink-free form of `path` for the link log, so `unlink`
+/// works from any directory.
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn temp_sibling(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.deduck-tmp", name))
+}
+
+#[cfg(unix)]
+fn same_device(a: &Path, b: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(fs::metadata(a)?.dev() == fs::metadata(b)?.dev())
+}
+
+#[cfg(not(unix))]
+fn same_device(_a: &Path, _b: &Path) -> io::Result<bool> {
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn same_inode(a: &Path, b: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+    let (meta_a, meta_b) = (fs::metadata(a)?, fs::metadata(b)?);
+    Ok(meta_a.dev() == meta_b.dev() && meta_a.ino() == meta_b.ino())
+}
+
+#[cfg(not(unix))]
+fn same_inode(_a: &Path, _b: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlink replacement is only supported on Unix",
+    ))
+}
@@ -1,21 +1,19 @@
-mod commands;
-mod config;
-mod duplicates;
-mod filters;
-mod hasher;
-mod prompts;
-mod quarantine;
-mod report;
-mod scanner;
-mod similar;
-mod utils;
-
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
-use commands::{clean, filter, purge, restore, scan};
-use config::get_dir_or_saved;
+use deduck::commands::{
+    clean, filter, link, purge, quarantine, reflink, restore, scan, symlink, tui, unlink,
+};
+use deduck::config::{self, get_dirs_or_saved};
+use deduck::filters::{ExtensionFilter, PRESETS, SizeFilter, parse_size};
+use deduck::options::{CleanAction, OutputFormat, ScanMode, ScanOptions};
+use deduck::policy::{KeepPolicy, KeepRule};
+use deduck::quarantine::Retention;
+use deduck::scanner::{IgnoreRules, WalkOptions};
+use deduck::utils::parse_duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "deduck", version = "0.1.0", author = "Yuvraj Biswal")]
@@ -26,30 +24,272 @@ use config::get_dir_or_saved;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Directory to scan; repeat to find duplicates across several roots
     #[arg(global = true, short, long)]
-    dir: Option<PathBuf>,
+    dir: Vec<PathBuf>,
+    /// Trusted directory whose files are kept and never removed; only copies
+    /// elsewhere that also exist here are acted on (repeatable)
+    #[arg(global = true, long)]
+    reference: Vec<PathBuf>,
+    /// Only consider files with these extensions (comma separated)
+    #[arg(global = true, long, value_delimiter = ',')]
+    include_ext: Vec<String>,
+    /// Never consider files with these extensions (comma separated)
+    #[arg(global = true, long, value_delimiter = ',')]
+    exclude_ext: Vec<String>,
+    /// Only consider files from a named extension preset
+    #[arg(
+        global = true,
+        long,
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(PRESETS.iter().map(|(name, _)| *name))
+    )]
+    preset: Vec<String>,
+    /// Skip files smaller than this size (e.g. 10K, 1.5M, 2G)
+    #[arg(global = true, long, value_parser = parse_size)]
+    min_size: Option<u64>,
+    /// Skip files larger than this size (e.g. 10K, 1.5M, 2G)
+    #[arg(global = true, long, value_parser = parse_size)]
+    max_size: Option<u64>,
+    /// Also consider empty files, which are skipped by default
+    #[arg(global = true, long)]
+    include_empty: bool,
+    /// Skip paths matching this gitignore-style glob (repeatable)
+    #[arg(global = true, long)]
+    exclude: Vec<String>,
+    /// Only scan files matching this gitignore-style glob (repeatable)
+    #[arg(global = true, long)]
+    include: Vec<String>,
+    /// Also honor .gitignore files and skip .git directories
+    #[arg(global = true, long)]
+    gitignore: bool,
+    /// Follow symlinks to files and directories (loops are skipped)
+    #[arg(global = true, long)]
+    follow_symlinks: bool,
+    /// Stay on the filesystem of each scanned directory
+    #[arg(global = true, long)]
+    one_file_system: bool,
+    /// Skip mounts of these filesystem types, e.g. proc,sysfs,fuse
+    #[arg(global = true, long, value_delimiter = ',')]
+    skip_fs_type: Vec<String>,
+    /// Ordered rules choosing which copy to keep: oldest, newest, shortest,
+    /// longest, alpha, prefer=DIR, protect=DIR (comma separated)
+    #[arg(global = true, long, value_delimiter = ',')]
+    keep: Vec<KeepRule>,
+    /// Output format for scan results
+    #[arg(global = true, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    /// Keep quarantined files in this store directory instead of each root
+    #[arg(global = true, long)]
+    quarantine_dir: Option<PathBuf>,
+    /// Keep quarantined files in the central store under the data directory
+    #[arg(global = true, long)]
+    central_quarantine: bool,
+    /// Rehash every file instead of reusing hashes from earlier runs
+    #[arg(global = true, long)]
+    no_cache: bool,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     Scan,
-    Filter,
-    Clean,
-    Restore,
-    Purge,
+    Filter {
+        /// Scan mode to use instead of prompting
+        #[arg(long, value_enum)]
+        mode: Option<ScanMode>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Quarantine the duplicates found by the last `filter` run
+    Clean {
+        /// Cleaning action to take instead of prompting
+        #[arg(long, value_enum)]
+        action: Option<CleanAction>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+        /// Review each group and pick the copies to keep before quarantining
+        #[arg(long)]
+        review: bool,
+        /// Show what would be quarantined or deleted without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Replace duplicate copies with hardlinks to the kept file
+    Link {
+        /// Scan mode to use instead of prompting
+        #[arg(long, value_enum)]
+        mode: Option<ScanMode>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+        /// Show which files would be linked without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Share extents between duplicates on filesystems with reflink support
+    Reflink {
+        /// Scan mode to use instead of prompting
+        #[arg(long, value_enum)]
+        mode: Option<ScanMode>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+        /// Show which files would be linked without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Replace duplicate copies with relative symlinks to the kept file
+    Symlink {
+        /// Scan mode to use instead of prompting
+        #[arg(long, value_enum)]
+        mode: Option<ScanMode>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+        /// Show which files would be linked without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Browse duplicate groups in a full-screen view and pick what to quarantine
+    Tui {
+        /// Scan mode to use instead of prompting
+        #[arg(long, value_enum)]
+        mode: Option<ScanMode>,
+        /// Accept defaults instead of prompting
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Turn links created by deduck back into independent copies
+    Unlink,
+    Restore {
+        /// Show which files would be restored without moving them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Delete quarantined files, or only those past --older-than or over
+    /// --max-total
+    Purge {
+        /// Show which files would be deleted without deleting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Only delete files quarantined longer ago than this (e.g. 12h, 30d, 2w)
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Option<u64>,
+        /// Delete the oldest quarantined files until the quarantines of the
+        /// scanned roots fit in this size (e.g. 500M, 20G)
+        #[arg(long, value_parser = parse_size)]
+        max_total: Option<u64>,
+    },
+    /// Inspect quarantined files
+    Quarantine {
+        #[command(subcommand)]
+        command: QuarantineCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum QuarantineCommand {
+    /// Show every known quarantine folder with its size and age
+    List,
 }
 
 fn main() {
     let cli = Cli::parse();
+    let location =
+        config::quarantine_location(cli.quarantine_dir.as_deref(), cli.central_quarantine);
 
-    let dir = get_dir_or_saved(&cli.dir);
+    // Listing needs no directory; it also shows quarantines of saved roots.
+    if let Commands::Quarantine {
+        command: QuarantineCommand::List,
+    } = &cli.command
+    {
+        let roots = if cli.dir.is_empty() {
+            config::load_last_dirs()
+        } else {
+            cli.dir.clone()
+        };
+        if let Err(e) = quarantine::run_list(&roots, &location, cli.format) {
+            eprintln!("Error: {}", e);
+            exit(1);
+        }
+        return;
+    }
+
+    let dirs = get_dirs_or_saved(&cli.dir);
+    let options = ScanOptions {
+        ext_filter: ExtensionFilter::new(&cli.include_ext, &cli.exclude_ext, &cli.preset),
+        size_filter: SizeFilter {
+            min: cli.min_size,
+            max: cli.max_size,
+            include_empty: cli.include_empty,
+        },
+        ignore: IgnoreRules {
+            excludes: cli.exclude.clone(),
+            includes: cli.include.clone(),
+            gitignore: cli.gitignore,
+            skip_dirs: location
+                .store()
+                .map(Path::to_path_buf)
+                .into_iter()
+                .collect(),
+        },
+        walk: WalkOptions {
+            follow_symlinks: cli.follow_symlinks,
+            one_file_system: cli.one_file_system,
+            skip_fs_types: cli.skip_fs_type.clone(),
+        },
+        keep_policy: KeepPolicy::new(cli.keep.clone()),
+        cache_file: if cli.no_cache {
+            None
+        } else {
+            config::hash_cache_path()
+        },
+        references: cli.reference.clone(),
+        ..ScanOptions::with_roots(dirs.clone())
+    };
 
     let result = match &cli.command {
-        Commands::Scan => scan::run_scan(dir.as_path()),
-        Commands::Filter => filter::run_filter(dir.as_path()),
-        Commands::Clean => clean::run_clean(dir.as_path()),
-        Commands::Restore => restore::run_restore(dir.as_path()),
-        Commands::Purge => purge::run_purge(dir.as_path()),
+        Commands::Scan => scan::run_scan(&options),
+        Commands::Filter { mode, yes } => filter::run_filter(options, *mode, *yes, cli.format),
+        Commands::Clean {
+            action,
+            yes,
+            review,
+            dry_run,
+        } => clean::run_clean(
+            options, &location, *action, *yes, *review, *dry_run, cli.format,
+        ),
+        Commands::Link { mode, yes, dry_run } => {
+            link::run_link(options, *mode, *yes, *dry_run, cli.format)
+        }
+        Commands::Reflink { mode, yes, dry_run } => {
+            reflink::run_reflink(options, *mode, *yes, *dry_run, cli.format)
+        }
+        Commands::Symlink { mode, yes, dry_run } => {
+            symlink::run_symlink(options, *mode, *yes, *dry_run, cli.format)
+        }
+        Commands::Tui { mode, yes } => tui::run_tui(options, &location, *mode, *yes),
+        Commands::Unlink => unlink::run_unlink(),
+        Commands::Restore { dry_run } => {
+            restore::run_restore(&dirs, &location, *dry_run, cli.format)
+        }
+        Commands::Purge {
+            dry_run,
+            older_than,
+            max_total,
+        } => match purge_cap(*max_total, &cli) {
+            Ok(max_bytes) => {
+                let retention = Retention {
+                    older_than: *older_than,
+                    max_bytes,
+                };
+                purge::run_purge(&dirs, &location, &retention, *dry_run, cli.format)
+            }
+            Err(e) => Err(e),
+        },
+        Commands::Quarantine { .. } => unreachable!("handled before resolving directories"),
     };
 
     if let Err(e) = result {
@@ -57,3 +297,18 @@ fn main() {
         exit(1);
     }
 }
+
+/// The size cap for `purge`. The global `--max-size` is read as an alias of
+/// `--max-total` there, so it can never be mistaken for "no limit" and
+/// delete the whole quarantine.
+fn purge_cap(max_total: Option<u64>, cli: &Cli) -> io::Result<Option<u64>> {
+    if cli.min_size.is_some() {
+        return Err(io::Error::other("--min-size cannot be used with purge"));
+    }
+    match (max_total, cli.max_size) {
+        (Some(total), Some(size)) if total != size => Err(io::Error::other(
+            "--max-size and --max-total disagree; pass only --max-total",
+        )),
+        (total, size) => Ok(total.or(size)),
+    }
+}
@@ -0,0 +1,95 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the kernel lists mounted filesystems on Linux.
+const MOUNT_TABLE: &str = "/proc/self/mounts";
+
+/// A mounted filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub fs_type: String,
+}
+
+/// Every mounted filesystem. Empty where the platform has no mount table.
+pub fn mounts() -> Vec<Mount> {
+    fs::read_to_string(MOUNT_TABLE)
+        .map(|content| parse_mounts(&content))
+        .unwrap_or_default()
+}
+
+/// Parses a `/proc/mounts` style table: device, mount point and type come
+/// first on each line, with spaces in paths escaped as octal.
+pub fn parse_mounts(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let path = fields.next()?;
+            let fs_type = fields.next()?;
+            Some(Mount {
+                path: PathBuf::from(unescape(path)),
+                fs_type: fs_type.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether `fs_type` is `name` or one of its subtypes, so `fuse` matches
+/// `fuse.sshfs`.
+pub fn fs_type_matches(fs_type: &str, name: &str) -> bool {
+    fs_type == name
+        || fs_type
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Devices of the mounted filesystems whose type matches one of `types`.
+pub fn devices_of_types(types: &[String]) -> Vec<u64> {
+    if types.is_empty() {
+        return Vec::new();
+    }
+
+    let mut devices = Vec::new();
+    for mount in mounts() {
+        if types.iter().any(|t| fs_type_matches(&mount.fs_type, t))
+            && let Some(dev) = device_of(&mount.path)
+            && !devices.contains(&dev)
+        {
+            devices.push(dev);
+        }
+    }
+    devices
+}
+
+pub fn device_of(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().and_then(|m| device(&m))
+}
+
+/// Whether `a` and `b` live on the same filesystem, so one can be renamed
+/// onto the other. Assumed true when either cannot be checked.
+pub fn same_device(a: &Path, b: &Path) -> bool {
+    match (device_of(a), device_of(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+#[cfg(unix)]
+pub fn device(metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device(_metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
+
+fn unescape(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
@@ -0,0 +1,82 @@
+use std::fs::{self, File, FileTimes, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use crate::hasher::{HashAlgorithm, hash_file};
+use crate::mounts;
+use crate::report::MoveMethod;
+
+/// Moves `src` to `dest`, renaming when both are on one filesystem and
+/// falling back to [`move_by_copy`] when they are not.
+pub fn move_file(src: &Path, dest: &Path) -> io::Result<MoveMethod> {
+    if let Some(parent) = dest.parent()
+        && !mounts::same_device(src, parent)
+    {
+        move_by_copy(src, dest)?;
+        return Ok(MoveMethod::Copy);
+    }
+
+    match fs::rename(src, dest) {
+        Ok(()) => Ok(MoveMethod::Rename),
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            move_by_copy(src, dest)?;
+            Ok(MoveMethod::Copy)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Moves `src` to `dest` by copying. The copy keeps the original's
+/// permissions and timestamps, is flushed to disk and must hash the same as
+/// the original before the original is removed. `dest` must not exist; on
+/// any failure the original is left untouched and the partial copy removed.
+pub fn move_by_copy(src: &Path, dest: &Path) -> io::Result<()> {
+    let metadata = fs::metadata(src)?;
+    let expected = hash_file(src, &HashAlgorithm::Blake3)?;
+
+    let mut writer = OpenOptions::new().write(true).create_new(true).open(dest)?;
+    let result = copy_into(src, &mut writer, &metadata)
+        .and_then(|()| verify_copy(dest, &expected))
+        .and_then(|()| fs::remove_file(src));
+    drop(writer);
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(dest);
+        return Err(e);
+    }
+
+    sync_parent(dest);
+    Ok(())
+}
+
+fn copy_into(src: &Path, writer: &mut File, metadata: &fs::Metadata) -> io::Result<()> {
+    io::copy(&mut File::open(src)?, writer)?;
+    writer.set_permissions(metadata.permissions())?;
+    writer.set_times(
+        FileTimes::new()
+            .set_accessed(metadata.accessed()?)
+            .set_modified(metadata.modified()?),
+    )?;
+    writer.sync_all()
+}
+
+fn verify_copy(dest: &Path, expected: &str) -> io::Result<()> {
+    if hash_file(dest, &HashAlgorithm::Blake3)? == expected {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "copy does not match the original",
+        ))
+    }
+}
+
+/// Flushes the directory entry of a new file. Best effort: not every
+/// platform can open a directory for syncing.
+fn sync_parent(path: &Path) {
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+}
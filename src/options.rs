@@ -0,0 +1,115 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::filters::{ExtensionFilter, SizeFilter};
+use crate::hasher::HashAlgorithm;
+use crate::policy::KeepPolicy;
+use crate::scanner::{IgnoreRules, WalkOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanMode {
+    Quick,
+    Normal,
+    Deep,
+}
+
+impl ScanMode {
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(ScanMode::Quick),
+            1 => Some(ScanMode::Normal),
+            2 => Some(ScanMode::Deep),
+            _ => None,
+        }
+    }
+
+    pub fn algorithm(self) -> HashAlgorithm {
+        match self {
+            ScanMode::Quick => HashAlgorithm::XxHash,
+            ScanMode::Normal => HashAlgorithm::Blake3,
+            ScanMode::Deep => HashAlgorithm::Sha256,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            ScanMode::Quick => 0,
+            ScanMode::Normal => 1,
+            ScanMode::Deep => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CleanAction {
+    /// Move duplicates into the quarantine folder
+    Separate,
+    /// Quarantine duplicates, then delete the quarantine folder
+    Clean,
+}
+
+impl CleanAction {
+    pub fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(CleanAction::Separate),
+            1 => Some(CleanAction::Clean),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable output
+    #[default]
+    Text,
+    /// A single JSON document
+    Json,
+    /// One JSON object per line
+    Ndjson,
+}
+
+/// What to scan and how. Built by the CLI from its flags, or directly by
+/// library users.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Directories scanned together; duplicates are found across all of them.
+    pub roots: Vec<PathBuf>,
+    /// Trusted directories: scanned and used as keepers, but never acted on.
+    /// Only groups with a copy in one of them are reported.
+    pub references: Vec<PathBuf>,
+    pub mode: ScanMode,
+    pub ext_filter: ExtensionFilter,
+    pub size_filter: SizeFilter,
+    pub ignore: IgnoreRules,
+    pub walk: WalkOptions,
+    /// Maximum perceptual hash distance for images to count as similar.
+    pub similarity_threshold: u32,
+    /// Decides which copy of each group survives.
+    pub keep_policy: KeepPolicy,
+    /// Where hashes are cached between runs. `None` hashes every file afresh.
+    pub cache_file: Option<PathBuf>,
+}
+
+impl ScanOptions {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        ScanOptions::with_roots(vec![root.into()])
+    }
+
+    pub fn with_roots(roots: Vec<PathBuf>) -> Self {
+        ScanOptions {
+            roots,
+            references: Vec::new(),
+            mode: ScanMode::Normal,
+            ext_filter: ExtensionFilter::default(),
+            size_filter: SizeFilter::default(),
+            ignore: IgnoreRules::default(),
+            walk: WalkOptions::default(),
+            similarity_threshold: 10,
+            keep_policy: KeepPolicy::default(),
+            cache_file: None,
+        }
+    }
+}
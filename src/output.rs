@@ -0,0 +1,188 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::io;
+
+use crate::duplicates::{DuplicateGroup, HardlinkGroup, ScanResult, SimilarGroup};
+use crate::options::OutputFormat;
+use crate::quarantine::{QuarantineSummary, unix_now};
+use crate::report::Report;
+use crate::utils::format_age;
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub files_found: usize,
+    pub duplicate_groups: usize,
+    pub duplicate_files: usize,
+    pub reclaimable_bytes: u64,
+    pub similar_groups: usize,
+    pub hardlink_groups: usize,
+}
+
+impl Summary {
+    pub fn new(result: &ScanResult) -> Self {
+        let groups = &result.duplicates;
+        Summary {
+            files_found: result.files_found,
+            duplicate_groups: groups.len(),
+            duplicate_files: groups.iter().map(|g| g.files.len()).sum(),
+            reclaimable_bytes: groups.iter().map(DuplicateGroup::reclaimable).sum(),
+            similar_groups: result.similar.len(),
+            hardlink_groups: result.hardlinks.len(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    duplicates: &'a [DuplicateGroup],
+    similar: &'a [SimilarGroup],
+    hardlinks: &'a [HardlinkGroup],
+    summary: Summary,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Duplicate(&'a DuplicateGroup),
+    Similar(&'a SimilarGroup),
+    Hardlink(&'a HardlinkGroup),
+    Summary(&'a Summary),
+    Report(&'a Report),
+    Quarantine(&'a QuarantineSummary),
+}
+
+/// Progress bar shown while a scan hashes files. Its length is set by the scan.
+pub fn hashing_progress_bar() -> ProgressBar {
+    let pb = ProgressBar::new(0);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} files")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    pb.set_message("🔍 Hashing files...");
+    pb
+}
+
+pub fn print_scan_result(format: OutputFormat, result: &ScanResult) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => print_text(result),
+        OutputFormat::Json => {
+            let document = Document {
+                duplicates: &result.duplicates,
+                similar: &result.similar,
+                hardlinks: &result.hardlinks,
+                summary: Summary::new(result),
+            };
+            println!("{}", to_json(&document, true)?);
+        }
+        OutputFormat::Ndjson => {
+            for group in &result.similar {
+                println!("{}", to_json(&Record::Similar(group), false)?);
+            }
+            for group in &result.duplicates {
+                println!("{}", to_json(&Record::Duplicate(group), false)?);
+            }
+            for group in &result.hardlinks {
+                println!("{}", to_json(&Record::Hardlink(group), false)?);
+            }
+            let summary = Summary::new(result);
+            println!("{}", to_json(&Record::Summary(&summary), false)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints what a command did, or would do in a dry run.
+pub fn print_report(format: OutputFormat, report: &Report) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => report.display(),
+        OutputFormat::Json => println!("{}", to_json(report, true)?),
+        OutputFormat::Ndjson => println!("{}", to_json(&Record::Report(report), false)?),
+    }
+
+    Ok(())
+}
+
+pub fn print_quarantines(format: OutputFormat, summaries: &[QuarantineSummary]) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => print_quarantines_text(summaries),
+        OutputFormat::Json => println!("{}", to_json(&summaries, true)?),
+        OutputFormat::Ndjson => {
+            for summary in summaries {
+                println!("{}", to_json(&Record::Quarantine(summary), false)?);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn to_json<T: Serialize>(value: &T, pretty: bool) -> io::Result<String> {
+    let result = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    result.map_err(io::Error::other)
+}
+
+fn print_text(result: &ScanResult) {
+    if result.files_found == 0 {
+        println!("❌ No files found in the directory.");
+        return;
+    }
+
+    for group in &result.similar {
+        println!("\n🖼️ Visually similar images:");
+        println!("   Base: {}", group.base.path.display());
+        for file in &group.similar {
+            println!("   ↳ {}", file.path.display());
+        }
+    }
+
+    for group in &result.duplicates {
+        println!("\n🔁 Duplicate Hash: {}", group.hash);
+        for file in &group.files {
+            println!("    {}", file.path.display());
+        }
+    }
+
+    for group in &result.hardlinks {
+        println!("\n🔗 Hardlinks to one file (counted once, not duplicates):");
+        for file in &group.files {
+            println!("    {}", file.display());
+        }
+    }
+
+    if result.is_empty() {
+        println!("✅ No duplicate or similar files found.");
+    }
+}
+
+fn print_quarantines_text(summaries: &[QuarantineSummary]) {
+    if summaries.is_empty() {
+        println!("✅ No quarantined files found.");
+        return;
+    }
+
+    let now = unix_now();
+    for summary in summaries {
+        println!("\n📦 {}", summary.dir.display());
+        if let Some(root) = &summary.root {
+            println!("  Root   : {}", root.display());
+        }
+        println!("  Files  : {}", summary.files);
+        println!(
+            "  Size   : {:.2} MB",
+            summary.bytes as f64 / (1024.0 * 1024.0)
+        );
+        if let Some(oldest) = summary.oldest {
+            println!("  Oldest : {} ago", format_age(now.saturating_sub(oldest)));
+        }
+        if let Some(newest) = summary.newest {
+            println!("  Newest : {} ago", format_age(now.saturating_sub(newest)));
+        }
+    }
+}
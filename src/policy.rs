@@ -0,0 +1,149 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::duplicates::{DuplicateGroup, FileEntry, ScanResult};
+
+/// One criterion for picking the copy that survives. Rules are applied in
+/// order; later rules only break ties left by earlier ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeepRule {
+    Oldest,
+    Newest,
+    ShortestPath,
+    LongestPath,
+    Alphabetical,
+    /// Prefer copies under this directory.
+    Prefer(PathBuf),
+    /// Never act on copies under this directory; they are also preferred.
+    Protect(PathBuf),
+}
+
+impl FromStr for KeepRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(dir) = s.strip_prefix("prefer=") {
+            return Ok(KeepRule::Prefer(normalize_dir(dir)));
+        }
+        if let Some(dir) = s.strip_prefix("protect=") {
+            return Ok(KeepRule::Protect(normalize_dir(dir)));
+        }
+
+        match s {
+            "oldest" => Ok(KeepRule::Oldest),
+            "newest" => Ok(KeepRule::Newest),
+            "shortest" => Ok(KeepRule::ShortestPath),
+            "longest" => Ok(KeepRule::LongestPath),
+            "alpha" | "alphabetical" => Ok(KeepRule::Alphabetical),
+            _ => Err(format!(
+                "unknown keep rule `{}` (expected oldest, newest, shortest, longest, alpha, prefer=DIR or protect=DIR)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeepPolicy {
+    pub rules: Vec<KeepRule>,
+}
+
+impl KeepPolicy {
+    pub fn new(rules: Vec<KeepRule>) -> Self {
+        KeepPolicy { rules }
+    }
+
+    /// Orders two copies by how strongly they should be kept, `Less` meaning
+    /// `a` wins. Falls back to path order so the result never depends on scan
+    /// order.
+    pub fn compare(&self, a: &FileEntry, b: &FileEntry) -> Ordering {
+        for rule in &self.rules {
+            let ordering = match rule {
+                KeepRule::Oldest => compare_mtime(a.mtime, b.mtime, false),
+                KeepRule::Newest => compare_mtime(a.mtime, b.mtime, true),
+                KeepRule::ShortestPath => path_len(&a.path).cmp(&path_len(&b.path)),
+                KeepRule::LongestPath => path_len(&b.path).cmp(&path_len(&a.path)),
+                KeepRule::Alphabetical => a.path.cmp(&b.path),
+                KeepRule::Prefer(dir) | KeepRule::Protect(dir) => {
+                    is_under(&b.path, dir).cmp(&is_under(&a.path, dir))
+                }
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        a.path.cmp(&b.path)
+    }
+
+    /// This policy with every directory in `references` protected ahead of
+    /// the other rules, so copies there are always kept.
+    pub fn with_references(&self, references: &[PathBuf]) -> KeepPolicy {
+        let mut rules: Vec<KeepRule> = references
+            .iter()
+            .map(|dir| KeepRule::Protect(normalize_dir(&dir.to_string_lossy())))
+            .collect();
+        rules.extend(self.rules.iter().cloned());
+        KeepPolicy { rules }
+    }
+
+    pub fn is_protected(&self, path: &Path) -> bool {
+        self.rules.iter().any(|rule| match rule {
+            KeepRule::Protect(dir) => is_under(path, dir),
+            _ => false,
+        })
+    }
+
+    /// Sorts `files` so the keeper comes first and marks protected files.
+    pub fn apply_to_files(&self, files: &mut [FileEntry]) {
+        files.sort_by(|a, b| self.compare(a, b));
+        for file in files.iter_mut() {
+            file.protected = self.is_protected(&file.path);
+        }
+    }
+
+    pub fn apply(&self, group: &mut DuplicateGroup) {
+        self.apply_to_files(&mut group.files);
+    }
+
+    /// Applies the policy to every duplicate and similar group in `result`.
+    pub fn apply_all(&self, result: &mut ScanResult) {
+        for group in &mut result.duplicates {
+            self.apply(group);
+        }
+
+        for group in &mut result.similar {
+            let mut files = vec![group.base.clone()];
+            files.append(&mut group.similar);
+            self.apply_to_files(&mut files);
+
+            group.base = files.remove(0);
+            group.similar = files;
+        }
+    }
+}
+
+/// Unknown mtimes sort after known ones either way.
+fn compare_mtime(a: Option<u64>, b: Option<u64>, newest_first: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if newest_first => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn path_len(path: &Path) -> usize {
+    path.as_os_str().len()
+}
+
+fn normalize_dir(dir: &str) -> PathBuf {
+    fs::canonicalize(dir).unwrap_or_else(|_| PathBuf::from(dir))
+}
+
+fn is_under(path: &Path, dir: &Path) -> bool {
+    path.starts_with(dir) || fs::canonicalize(path).is_ok_and(|p| p.starts_with(dir))
+}
@@ -1,5 +1,7 @@
 use dialoguer::Select;
-use std::io;
+use std::io::{self, IsTerminal};
+
+use crate::options::{CleanAction, ScanMode};
 
 pub fn prompt_scan_mode() -> io::Result<usize> {
     let scan_modes = &["Quick Scan", "Normal Scan", "Deep Scan"];
@@ -8,7 +10,7 @@ pub fn prompt_scan_mode() -> io::Result<usize> {
         .default(1)
         .items(scan_modes)
         .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+        .map_err(|e| io::Error::other(e.to_string()))
 }
 
 pub fn prompt_clean_choice() -> io::Result<usize> {
@@ -18,5 +20,41 @@ pub fn prompt_clean_choice() -> io::Result<usize> {
         .default(0)
         .items(clean_modes)
         .interact()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))
+        .map_err(|e| io::Error::other(e.to_string()))
+}
+
+/// Uses the mode given on the command line, falling back to the prompt only
+/// when a terminal is attached. `--yes` accepts the default without asking.
+pub fn resolve_scan_mode(flag: Option<ScanMode>, yes: bool) -> io::Result<ScanMode> {
+    if let Some(mode) = flag {
+        return Ok(mode);
+    }
+    if yes {
+        return Ok(ScanMode::Normal);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::other(
+            "No --mode given and no terminal attached to prompt for one",
+        ));
+    }
+
+    let choice = prompt_scan_mode()?;
+    Ok(ScanMode::from_index(choice).unwrap_or(ScanMode::Normal))
+}
+
+pub fn resolve_clean_action(flag: Option<CleanAction>, yes: bool) -> io::Result<CleanAction> {
+    if let Some(action) = flag {
+        return Ok(action);
+    }
+    if yes {
+        return Ok(CleanAction::Separate);
+    }
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::other(
+            "No --action given and no terminal attached to prompt for one",
+        ));
+    }
+
+    let choice = prompt_clean_choice()?;
+    Ok(CleanAction::from_index(choice).unwrap_or(CleanAction::Separate))
 }
@@ -1,32 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::hasher::{HashAlgorithm, hash_file};
+use crate::mover::move_file;
+use crate::report::{MoveMethod, MoveOutcome, Report};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const QUARANTINE_DIR_NAME: &str = ".deduck_quarantine";
+
+/// A single quarantined file and everything needed to put it back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    /// Absolute path the file was moved away from.
+    pub original_path: PathBuf,
+    /// File name inside the quarantine directory.
+    pub stored_name: String,
+    pub size: u64,
+    /// BLAKE3 hash of the content at quarantine time.
+    pub hash: String,
+    /// Seconds since the Unix epoch.
+    pub quarantined_at: u64,
+    pub session_id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    /// The scan root the files were quarantined from, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<PathBuf>,
+    pub entries: Vec<QuarantineEntry>,
+}
+
+impl Manifest {
+    pub fn load(quarantine_dir: &Path) -> io::Result<Self> {
+        let path = manifest_path(quarantine_dir);
+        if !path.exists() {
+            return Ok(Manifest::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, quarantine_dir: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(manifest_path(quarantine_dir), content)
+    }
+}
+
+/// Where quarantined files are kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum QuarantineLocation {
+    /// A `.deduck_quarantine` folder inside each scan root.
+    #[default]
+    InRoot,
+    /// A store directory holding one quarantine folder per scan root.
+    Store(PathBuf),
+}
+
+impl QuarantineLocation {
+    /// The quarantine folder for files found under `root`.
+    pub fn dir_for(&self, root: &Path) -> PathBuf {
+        match self {
+            QuarantineLocation::InRoot => get_quarantine_dir(root),
+            QuarantineLocation::Store(store) => store.join(store_slot(root)),
+        }
+    }
+
+    pub fn store(&self) -> Option<&Path> {
+        match self {
+            QuarantineLocation::InRoot => None,
+            QuarantineLocation::Store(store) => Some(store),
+        }
+    }
+}
 
 pub fn get_quarantine_dir(base_dir: &Path) -> PathBuf {
-    base_dir.join(".deduck_quarantine")
+    base_dir.join(QUARANTINE_DIR_NAME)
+}
+
+/// Name of the folder holding `root`'s quarantine inside a store: the root's
+/// own name, made unique by a hash of its full path.
+pub fn store_slot(root: &Path) -> String {
+    let canonical = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let name = canonical
+        .file_name()
+        .map_or_else(|| "root".into(), |n| n.to_string_lossy());
+    let hash = blake3::hash(canonical.as_os_str().as_encoded_bytes()).to_hex();
+    format!("{}-{}", name, &hash[..12])
+}
+
+/// The quarantine folder of every root, listing roots that resolve to the
+/// same directory only once.
+pub fn quarantine_dirs(roots: &[PathBuf], location: &QuarantineLocation) -> Vec<PathBuf> {
+    let mut seen = Vec::new();
+    let mut dirs = Vec::new();
+
+    for root in roots {
+        let canonical = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+        if !seen.contains(&canonical) {
+            seen.push(canonical);
+            dirs.push(location.dir_for(root));
+        }
+    }
+
+    dirs
+}
+
+/// The root in `roots` that `path` lives under, preferring the innermost one
+/// when roots are nested.
+pub fn root_for<'a>(path: &Path, roots: &'a [PathBuf]) -> Option<&'a Path> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    roots
+        .iter()
+        .filter_map(|root| {
+            let canonical = fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+            path.starts_with(&canonical)
+                .then_some((canonical.components().count(), root.as_path()))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, root)| root)
+}
+
+/// Quarantines each file into the quarantine folder of the root it was found
+/// under, so files from different trees are kept apart. Files outside every
+/// root go to the first root's folder. Every root is processed even if one
+/// fails; the failures are returned together.
+pub fn quarantine_by_root(
+    files: Vec<PathBuf>,
+    roots: &[PathBuf],
+    location: &QuarantineLocation,
+    report: &mut Report,
+) -> io::Result<()> {
+    let mut by_root: Vec<(&Path, Vec<PathBuf>)> = Vec::new();
+
+    for file in files {
+        let Some(root) = root_for(&file, roots).or_else(|| roots.first().map(PathBuf::as_path))
+        else {
+            continue;
+        };
+        match by_root.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, group)) => group.push(file),
+            None => by_root.push((root, vec![file])),
+        }
+    }
+
+    // A root that fails must not keep the others from being quarantined.
+    let mut errors = Vec::new();
+    for (root, files) in by_root {
+        let quarantine_dir = location.dir_for(root);
+        let result = quarantine_duplicates(files, &quarantine_dir, report).and_then(|_| {
+            let mut manifest = Manifest::load(&quarantine_dir)?;
+            if manifest.root.is_none() {
+                manifest.root = Some(fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()));
+                manifest.save(&quarantine_dir)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            errors.push(format!("{}: {}", root.display(), e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::other(errors.join("; ")))
+    }
+}
+
+pub fn manifest_path(quarantine_dir: &Path) -> PathBuf {
+    quarantine_dir.join(MANIFEST_FILE)
 }
 
-pub fn quarantine_duplicates(files: Vec<PathBuf>, quarantine_dir: &Path) -> io::Result<()> {
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn new_session_id() -> String {
+    format!("{}-{}", unix_now(), std::process::id())
+}
+
+/// Moves `files` into `quarantine_dir` and records them in its manifest.
+/// Every attempted move is recorded in `report`.
+pub fn quarantine_duplicates(
+    files: Vec<PathBuf>,
+    quarantine_dir: &Path,
+    report: &mut Report,
+) -> io::Result<()> {
     fs::create_dir_all(quarantine_dir)?;
 
+    let mut manifest = Manifest::load(quarantine_dir)?;
+    let session_id = new_session_id();
+
     for file in files {
         if !file.exists() {
             eprintln!("File not found, skipping quarantine: {}", file.display());
             continue;
         }
 
-        if let Some(filename) = file.file_name() {
-            let dest = quarantine_dir.join(filename);
-            if let Err(e) = fs::rename(&file, &dest) {
-                eprintln!("Failed to quarantine file {}: {}", file.display(), e);
+        let Some(filename) = file.file_name() else {
+            continue;
+        };
+
+        // A file that cannot be read is skipped; the files already moved
+        // must still reach the manifest.
+        let (original_path, size, hash) = match describe(&file) {
+            Ok(described) => described,
+            Err(e) => {
+                record_move(report, &file, quarantine_dir, Err(e));
+                continue;
             }
+        };
+
+        let stored_name = unique_stored_name(quarantine_dir, &hash, &filename.to_string_lossy());
+        let dest = quarantine_dir.join(&stored_name);
+        if !record_move(report, &file, &dest, move_file(&file, &dest)) {
+            continue;
         }
+
+        manifest.entries.push(QuarantineEntry {
+            original_path,
+            stored_name,
+            size,
+            hash,
+            quarantined_at: unix_now(),
+            session_id: session_id.clone(),
+        });
     }
 
-    Ok(())
+    manifest.save(quarantine_dir)
+}
+
+/// Absolute path, size and BLAKE3 hash of a file about to be quarantined.
+fn describe(file: &Path) -> io::Result<(PathBuf, u64, String)> {
+    let original_path = fs::canonicalize(file)?;
+    let size = fs::metadata(file)?.len();
+    let hash = hash_file(file, &HashAlgorithm::Blake3)?;
+    Ok((original_path, size, hash))
+}
+
+/// Builds a quarantine file name from the content hash and original name,
+/// adding a numeric suffix until it is unused, so two quarantined files can
+/// never overwrite each other.
+fn unique_stored_name(quarantine_dir: &Path, hash: &str, filename: &str) -> String {
+    let prefix = &hash[..hash.len().min(16)];
+    let base = format!("{}_{}", prefix, filename);
+
+    let mut candidate = base.clone();
+    let mut counter = 1;
+    while quarantine_dir.join(&candidate).exists() || candidate == MANIFEST_FILE {
+        candidate = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    candidate
 }
 
-pub fn restore_quarantined(quarantine_dir: &Path, target_dir: &Path) -> io::Result<()> {
+/// Moves quarantined files back to the paths recorded in the manifest,
+/// recreating parent directories as needed, and records each restored path
+/// in `report`. Files that predate the manifest are restored into
+/// `target_dir`. In a dry run nothing is moved.
+pub fn restore_quarantined(
+    quarantine_dir: &Path,
+    target_dir: &Path,
+    report: &mut Report,
+) -> io::Result<()> {
     if !quarantine_dir.exists() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -34,14 +280,256 @@ pub fn restore_quarantined(quarantine_dir: &Path, target_dir: &Path) -> io::Resu
         ));
     }
 
+    let mut manifest = Manifest::load(quarantine_dir)?;
+    let failed_before = report.failed_moves().count();
+    let stored_names: Vec<String> = manifest
+        .entries
+        .iter()
+        .map(|e| e.stored_name.clone())
+        .collect();
+    let mut remaining = Vec::new();
+
+    for entry in manifest.entries.drain(..) {
+        let src = quarantine_dir.join(&entry.stored_name);
+        if !src.exists() {
+            eprintln!(
+                "Quarantined copy missing, skipping: {}",
+                entry.original_path.display()
+            );
+            continue;
+        }
+
+        if entry.original_path.exists() {
+            eprintln!(
+                "Refusing to overwrite existing file: {}",
+                entry.original_path.display()
+            );
+            remaining.push(entry);
+            continue;
+        }
+
+        if !report.dry_run {
+            // A parent that cannot be recreated fails only this entry.
+            let result = entry
+                .original_path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| move_file(&src, &entry.original_path));
+            if !record_move(report, &src, &entry.original_path, result) {
+                remaining.push(entry);
+                continue;
+            }
+        }
+        report.add_restored(entry.original_path);
+    }
+
     for entry in fs::read_dir(quarantine_dir)? {
         let entry = entry?;
         let file_path = entry.path();
         let file_name = file_path.file_name().unwrap();
+        if file_name == MANIFEST_FILE || stored_names.iter().any(|name| name.as_str() == file_name)
+        {
+            continue;
+        }
         let dest = target_dir.join(file_name);
-        fs::rename(file_path, dest)?;
+        if !report.dry_run && !record_move(report, &file_path, &dest, move_file(&file_path, &dest))
+        {
+            continue;
+        }
+        report.add_restored(dest);
+    }
+
+    if report.dry_run {
+        return Ok(());
+    }
+
+    if remaining.is_empty() && report.failed_moves().count() == failed_before {
+        fs::remove_dir_all(quarantine_dir)?;
+    } else {
+        Manifest {
+            root: manifest.root,
+            entries: remaining,
+        }
+        .save(quarantine_dir)?;
+    }
+    Ok(())
+}
+
+/// Limits `purge` enforces instead of deleting every quarantined file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Retention {
+    /// Purge files quarantined more than this many seconds ago.
+    pub older_than: Option<u64>,
+    /// Purge the oldest files until the quarantines hold at most this many
+    /// bytes.
+    pub max_bytes: Option<u64>,
+}
+
+impl Retention {
+    pub fn is_unlimited(&self) -> bool {
+        self.older_than.is_none() && self.max_bytes.is_none()
+    }
+}
+
+/// Deletes the quarantined files in `quarantine_dirs` that `retention` no
+/// longer allows keeping, oldest first, and records them in `report`. The
+/// size limit applies to all folders together. Ages come from the manifest,
+/// so files without an entry are kept. Folders left empty are removed. In a
+/// dry run nothing is deleted.
+pub fn expire_quarantined(
+    quarantine_dirs: &[PathBuf],
+    retention: &Retention,
+    now: u64,
+    report: &mut Report,
+) -> io::Result<()> {
+    let mut manifests = Vec::new();
+    for dir in quarantine_dirs {
+        if dir.exists() {
+            manifests.push((dir, Manifest::load(dir)?));
+        }
+    }
+
+    let mut by_age: Vec<(usize, usize)> = manifests
+        .iter()
+        .enumerate()
+        .flat_map(|(m, (_, manifest))| (0..manifest.entries.len()).map(move |e| (m, e)))
+        .collect();
+    by_age.sort_by_key(|&(m, e)| manifests[m].1.entries[e].quarantined_at);
+
+    let mut total: u64 = by_age
+        .iter()
+        .map(|&(m, e)| manifests[m].1.entries[e].size)
+        .sum();
+    let mut expired: HashSet<(usize, usize)> = HashSet::new();
+    for (m, e) in by_age {
+        let entry = &manifests[m].1.entries[e];
+        let too_old = retention
+            .older_than
+            .is_some_and(|age| now.saturating_sub(entry.quarantined_at) > age);
+        let too_big = retention.max_bytes.is_some_and(|max| total > max);
+        // Later entries are newer and the total only shrinks.
+        if !too_old && !too_big {
+            break;
+        }
+        total -= entry.size;
+        expired.insert((m, e));
+    }
+
+    for (m, (dir, manifest)) in manifests.into_iter().enumerate() {
+        let (gone, kept): (Vec<_>, Vec<_>) = manifest
+            .entries
+            .into_iter()
+            .enumerate()
+            .partition(|(e, _)| expired.contains(&(m, *e)));
+        if gone.is_empty() {
+            continue;
+        }
+
+        for (_, entry) in gone {
+            if !report.dry_run {
+                match fs::remove_file(dir.join(&entry.stored_name)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            }
+            report.add_file(entry.original_path, entry.size);
+        }
+
+        if report.dry_run {
+            continue;
+        }
+
+        let manifest = Manifest {
+            root: manifest.root,
+            entries: kept.into_iter().map(|(_, entry)| entry).collect(),
+        };
+        let only_manifest = fs::read_dir(dir)?
+            .filter_map(Result::ok)
+            .all(|entry| entry.file_name() == MANIFEST_FILE);
+        if manifest.entries.is_empty() && only_manifest {
+            fs::remove_dir_all(dir)?;
+        } else {
+            manifest.save(dir)?;
+        }
     }
 
-    fs::remove_dir_all(quarantine_dir)?;
     Ok(())
 }
+
+/// What a quarantine folder holds, for listing.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuarantineSummary {
+    pub dir: PathBuf,
+    pub root: Option<PathBuf>,
+    pub files: usize,
+    pub bytes: u64,
+    /// When the oldest and newest files were quarantined, in seconds since
+    /// the Unix epoch.
+    pub oldest: Option<u64>,
+    pub newest: Option<u64>,
+}
+
+pub fn summarize(quarantine_dir: &Path) -> io::Result<QuarantineSummary> {
+    let manifest = Manifest::load(quarantine_dir)?;
+    let root = manifest.root.clone().or_else(|| {
+        (quarantine_dir.file_name()? == QUARANTINE_DIR_NAME)
+            .then(|| quarantine_dir.parent().map(Path::to_path_buf))?
+    });
+
+    let mut summary = QuarantineSummary {
+        dir: quarantine_dir.to_path_buf(),
+        root,
+        files: manifest.entries.len(),
+        bytes: manifest.entries.iter().map(|e| e.size).sum(),
+        oldest: manifest.entries.iter().map(|e| e.quarantined_at).min(),
+        newest: manifest.entries.iter().map(|e| e.quarantined_at).max(),
+    };
+
+    // Files quarantined before manifests existed.
+    for entry in fs::read_dir(quarantine_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == MANIFEST_FILE
+            || manifest
+                .entries
+                .iter()
+                .any(|e| e.stored_name.as_str() == name)
+        {
+            continue;
+        }
+        if let Ok(metadata) = entry.metadata()
+            && metadata.is_file()
+        {
+            summary.files += 1;
+            summary.bytes += metadata.len();
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Records the outcome of moving `path` to `dest` in `report`, returning
+/// whether the file moved.
+fn record_move(
+    report: &mut Report,
+    path: &Path,
+    dest: &Path,
+    result: io::Result<MoveMethod>,
+) -> bool {
+    let (method, error) = match result {
+        Ok(method) => (Some(method), None),
+        Err(e) => {
+            eprintln!("⚠️ Failed to move {}: {}", path.display(), e);
+            (None, Some(e.to_string()))
+        }
+    };
+    let moved = method.is_some();
+
+    report.add_move(MoveOutcome {
+        path: path.to_path_buf(),
+        destination: dest.to_path_buf(),
+        method,
+        error,
+    });
+    moved
+}
@@ -0,0 +1,176 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+use crate::duplicates::DuplicateGroup;
+use crate::linker::{canonical, files_identical};
+use crate::report::{LinkKind, LinkRecord, Report};
+
+/// Shares extents between each redundant copy in `groups` and the kept file
+/// (`files[0]`) using the FIDEDUPERANGE ioctl. The kernel compares the ranges
+/// itself and only shares them if they are identical, so both files remain
+/// independently editable. Files on filesystems without reflink support
+/// (ext4, tmpfs, ...) are skipped. A dry run only compares the files.
+pub fn reflink_duplicates(groups: &[DuplicateGroup], report: &mut Report) -> io::Result<()> {
+    let mut unsupported = 0;
+
+    for group in groups {
+        let Some(keep) = group.files.first() else {
+            continue;
+        };
+
+        for file in group.redundant() {
+            let shared = if report.dry_run {
+                files_identical(&keep.path, &file.path)
+                    .map(|same| if same { group.size } else { 0 })
+            } else {
+                dedupe_file(&keep.path, &file.path)
+            };
+
+            match shared {
+                Ok(0) => {}
+                Ok(shared) => report.add_link(LinkRecord {
+                    path: canonical(&file.path),
+                    target: canonical(&keep.path),
+                    kind: LinkKind::Reflink,
+                    size: shared,
+                }),
+                Err(e) if e.kind() == io::ErrorKind::Unsupported => {
+                    if unsupported == 0 {
+                        eprintln!(
+                            "⚠️ The filesystem holding {} does not support reflinks; use `deduck link` or `deduck clean` there instead.",
+                            file.path.display()
+                        );
+                    }
+                    unsupported += 1;
+                }
+                Err(e) => eprintln!("❌ Failed to reflink {}: {}", file.path.display(), e),
+            }
+        }
+    }
+
+    if unsupported > 0 {
+        eprintln!(
+            "⚠️ Skipped {} file(s) on filesystems without reflink support.",
+            unsupported
+        );
+    }
+
+    Ok(())
+}
+
+/// Dedupes the whole of `dest` against `src`, returning the bytes shared.
+pub fn dedupe_file(src: &Path, dest: &Path) -> io::Result<u64> {
+    let src_file = File::open(src)?;
+    let dest_file = OpenOptions::new().write(true).open(dest)?;
+
+    let len = src_file.metadata()?.len();
+    if len != dest_file.metadata()?.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "file sizes differ",
+        ));
+    }
+
+    sys::dedupe_range(&src_file, &dest_file, len)
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+
+    // _IOWR(0x94, 54, struct file_dedupe_range)
+    const FIDEDUPERANGE: u64 = 0xC018_9436;
+    const FILE_DEDUPE_RANGE_DIFFERS: i32 = 1;
+    // Filesystems cap the length handled by a single call.
+    const CHUNK: u64 = 16 * 1024 * 1024;
+
+    #[repr(C)]
+    struct FileDedupeRangeInfo {
+        dest_fd: i64,
+        dest_offset: u64,
+        bytes_deduped: u64,
+        status: i32,
+        reserved: u32,
+    }
+
+    #[repr(C)]
+    struct FileDedupeRange {
+        src_offset: u64,
+        src_length: u64,
+        dest_count: u16,
+        reserved1: u16,
+        reserved2: u32,
+        info: [FileDedupeRangeInfo; 1],
+    }
+
+    pub fn dedupe_range(src: &File, dest: &File, len: u64) -> io::Result<u64> {
+        let mut offset = 0;
+
+        while offset < len {
+            let mut range = FileDedupeRange {
+                src_offset: offset,
+                src_length: (len - offset).min(CHUNK),
+                dest_count: 1,
+                reserved1: 0,
+                reserved2: 0,
+                info: [FileDedupeRangeInfo {
+                    dest_fd: dest.as_raw_fd() as i64,
+                    dest_offset: offset,
+                    bytes_deduped: 0,
+                    status: 0,
+                    reserved: 0,
+                }],
+            };
+
+            // SAFETY: `range` is a valid file_dedupe_range with one info entry
+            // and both descriptors stay open for the duration of the call.
+            let ret = unsafe { libc::ioctl(src.as_raw_fd(), FIDEDUPERANGE as _, &mut range) };
+            if ret < 0 {
+                return Err(map_error(io::Error::last_os_error()));
+            }
+
+            let info = &range.info[0];
+            if info.status == FILE_DEDUPE_RANGE_DIFFERS {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "content differs from the kept file",
+                ));
+            }
+            if info.status < 0 {
+                return Err(map_error(io::Error::from_raw_os_error(-info.status)));
+            }
+            if info.bytes_deduped == 0 {
+                break;
+            }
+            offset += info.bytes_deduped;
+        }
+
+        Ok(offset)
+    }
+
+    fn map_error(err: io::Error) -> io::Error {
+        match err.raw_os_error() {
+            Some(libc::EOPNOTSUPP)
+            | Some(libc::ENOTTY)
+            | Some(libc::EINVAL)
+            | Some(libc::EXDEV) => io::Error::new(io::ErrorKind::Unsupported, err),
+            _ => err,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod sys {
+    use std::fs::File;
+    use std::io;
+
+    pub fn dedupe_range(_src: &File, _dest: &File, _len: u64) -> io::Result<u64> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reflinks are only supported on Linux",
+        ))
+    }
+}
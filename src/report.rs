@@ -1,11 +1,61 @@
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Hardlink,
+    Reflink,
+    Symlink,
+}
+
+/// A redundant copy at `path` that was replaced by a link to `target`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub path: PathBuf,
+    pub target: PathBuf,
+    pub kind: LinkKind,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MoveMethod {
+    Rename,
+    /// Copied, verified and deleted because the destination is on another
+    /// filesystem.
+    Copy,
+}
+
+/// What happened to one file a command tried to move.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveOutcome {
+    pub path: PathBuf,
+    pub destination: PathBuf,
+    /// How the file was moved; `None` when it was left where it was.
+    pub method: Option<MoveMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
 pub struct Report {
+    /// Set when nothing is actually changed and the report only describes
+    /// what would happen.
+    pub dry_run: bool,
     pub files_found: usize,
     pub files_deleted: usize,
     pub space_freed: u64,
+    /// Bytes moved into quarantine, which stay in use until purged.
+    pub space_moved: u64,
     pub deleted_files: Vec<PathBuf>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub moved_files: Vec<PathBuf>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub restored_files: Vec<PathBuf>,
+    pub links: Vec<LinkRecord>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub moves: Vec<MoveOutcome>,
 }
 
 impl Report {
@@ -13,30 +63,138 @@ impl Report {
         Report::default()
     }
 
+    pub fn dry_run() -> Self {
+        Report {
+            dry_run: true,
+            ..Report::default()
+        }
+    }
+
     pub fn add_file(&mut self, path: PathBuf, size: u64) {
         self.files_deleted += 1;
         self.space_freed += size;
         self.deleted_files.push(path);
     }
 
+    /// A file moved into quarantine; its space is freed once it is purged.
+    pub fn add_moved(&mut self, path: PathBuf, size: u64) {
+        self.space_moved += size;
+        self.moved_files.push(path);
+    }
+
+    pub fn add_restored(&mut self, path: PathBuf) {
+        self.restored_files.push(path);
+    }
+
+    pub fn add_link(&mut self, record: LinkRecord) {
+        self.space_freed += record.size;
+        self.links.push(record);
+    }
+
+    pub fn add_move(&mut self, outcome: MoveOutcome) {
+        self.moves.push(outcome);
+    }
+
+    /// Moves that left their file in place.
+    pub fn failed_moves(&self) -> impl Iterator<Item = &MoveOutcome> {
+        self.moves.iter().filter(|m| m.error.is_some())
+    }
+
     pub fn set_files_found(&mut self, count: usize) {
         self.files_found = count;
     }
 
     pub fn display(&self) {
-        println!("\n📊 Cleanup Report:");
-        println!("  Files found     : {}", self.files_found);
-        println!("  Files deleted   : {}", self.files_deleted);
-        println!(
-            "  Space cleaned   : {:.2} MB",
-            self.space_freed as f64 / (1024.0 * 1024.0)
-        );
-
-        if !self.deleted_files.is_empty() {
-            println!("  Deleted files:");
-            for file in &self.deleted_files {
-                println!("    {}", file.display());
+        let mb = self.space_freed as f64 / (1024.0 * 1024.0);
+        let moved_mb = self.space_moved as f64 / (1024.0 * 1024.0);
+
+        if self.dry_run {
+            println!("\n📊 Dry Run Report (nothing was changed):");
+            println!("  Files found       : {}", self.files_found);
+            println!("  Files to delete   : {}", self.files_deleted);
+            if !self.moved_files.is_empty() {
+                println!("  Files to move     : {}", self.moved_files.len());
             }
+            if !self.links.is_empty() {
+                println!("  Files to link     : {}", self.links.len());
+            }
+            if !self.restored_files.is_empty() {
+                println!("  Files to restore  : {}", self.restored_files.len());
+            }
+            println!("  Space reclaimable : {:.2} MB", mb);
+            if !self.moved_files.is_empty() {
+                println!("  Space to move     : {:.2} MB", moved_mb);
+            }
+        } else {
+            println!("\n📊 Cleanup Report:");
+            println!("  Files found     : {}", self.files_found);
+            println!("  Files deleted   : {}", self.files_deleted);
+            if !self.moved_files.is_empty() {
+                println!("  Files moved     : {}", self.moved_files.len());
+            }
+            if !self.links.is_empty() {
+                println!("  Files linked    : {}", self.links.len());
+            }
+            if !self.restored_files.is_empty() {
+                println!("  Files restored  : {}", self.restored_files.len());
+            }
+            println!("  Space cleaned   : {:.2} MB", mb);
+            if !self.moved_files.is_empty() {
+                println!("  Space moved     : {:.2} MB", moved_mb);
+            }
+        }
+
+        let (deleted, moved, linked, restored) = if self.dry_run {
+            (
+                "Would delete",
+                "Would quarantine",
+                "Would link",
+                "Would restore",
+            )
+        } else {
+            (
+                "Deleted files",
+                "Quarantined files",
+                "Linked files",
+                "Restored files",
+            )
+        };
+
+        print_paths(deleted, &self.deleted_files);
+        print_paths(moved, &self.moved_files);
+
+        if !self.links.is_empty() {
+            println!("  {}:", linked);
+            for link in &self.links {
+                println!("    {} → {}", link.path.display(), link.target.display());
+            }
+        }
+
+        print_paths(restored, &self.restored_files);
+
+        let copied = self
+            .moves
+            .iter()
+            .filter(|m| m.method == Some(MoveMethod::Copy))
+            .count();
+        if copied > 0 {
+            println!("  Copied across filesystems: {}", copied);
+        }
+        if self.failed_moves().next().is_some() {
+            println!("  Failed to move:");
+            for outcome in self.failed_moves() {
+                let error = outcome.error.as_deref().unwrap_or_default();
+                println!("    {}: {}", outcome.path.display(), error);
+            }
+        }
+    }
+}
+
+fn print_paths(title: &str, paths: &[PathBuf]) {
+    if !paths.is_empty() {
+        println!("  {}:", title);
+        for path in paths {
+            println!("    {}", path.display());
         }
     }
 }
@@ -0,0 +1,204 @@
+use dialoguer::{Confirm, MultiSelect, Select};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use crate::duplicates::{FileEntry, ScanResult};
+use crate::utils::format_timestamp;
+
+/// A group as presented for review: every copy, keeper first.
+pub struct ReviewGroup<'a> {
+    pub title: String,
+    pub size: Option<u64>,
+    pub files: Vec<&'a FileEntry>,
+}
+
+/// Flattens duplicate and similar groups into the order they are reviewed.
+pub fn review_groups(result: &ScanResult) -> Vec<ReviewGroup<'_>> {
+    let mut groups = Vec::new();
+
+    for group in &result.duplicates {
+        groups.push(ReviewGroup {
+            title: format!("🔁 Duplicate Hash: {}", group.hash),
+            size: Some(group.size),
+            files: group.files.iter().collect(),
+        });
+    }
+
+    for group in &result.similar {
+        let mut files = vec![&group.base];
+        files.extend(group.similar.iter());
+        groups.push(ReviewGroup {
+            title: "🖼️ Visually similar images".to_string(),
+            size: None,
+            files,
+        });
+    }
+
+    groups
+}
+
+/// The set of directories a group's copies live in. Groups spanning the same
+/// directories can reuse a choice made for an earlier group.
+pub fn directory_set(files: &[&FileEntry]) -> BTreeSet<PathBuf> {
+    files.iter().map(|f| parent_dir(&f.path)).collect()
+}
+
+/// Files to remove from `files` when only copies in `kept_dirs` survive.
+/// Protected files are never removed.
+pub fn removals_for_dirs(files: &[&FileEntry], kept_dirs: &BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|f| !f.protected && !kept_dirs.contains(&parent_dir(&f.path)))
+        .map(|f| f.path.clone())
+        .collect()
+}
+
+fn parent_dir(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// What the user chose in a review: the copies to remove and every copy of
+/// the reviewed groups that stays.
+#[derive(Debug, Default)]
+pub struct Selection {
+    pub remove: Vec<PathBuf>,
+    pub keep: HashSet<PathBuf>,
+}
+
+impl Selection {
+    fn add_group(&mut self, files: &[&FileEntry], remove: Vec<PathBuf>) {
+        self.keep.extend(
+            files
+                .iter()
+                .filter(|f| !remove.contains(&f.path))
+                .map(|f| f.path.clone()),
+        );
+        self.remove.extend(remove);
+    }
+}
+
+/// Walks every group interactively and returns the files the user chose to
+/// remove and those kept in their place.
+pub fn review(result: &ScanResult) -> io::Result<Selection> {
+    if !io::stdin().is_terminal() {
+        return Err(io::Error::other("--review needs an interactive terminal"));
+    }
+
+    let groups = review_groups(result);
+    let total = groups.len();
+    let mut remembered: HashMap<BTreeSet<PathBuf>, BTreeSet<PathBuf>> = HashMap::new();
+    let mut selection = Selection::default();
+
+    for (index, group) in groups.iter().enumerate() {
+        let dirs = directory_set(&group.files);
+
+        if let Some(kept_dirs) = remembered.get(&dirs) {
+            selection.add_group(&group.files, removals_for_dirs(&group.files, kept_dirs));
+            continue;
+        }
+
+        println!("\n[{}/{}] {}", index + 1, total, group.title);
+        let labels: Vec<String> = group.files.iter().map(|f| label(f, group.size)).collect();
+        for line in &labels {
+            println!("    {}", line);
+        }
+
+        let actions = &[
+            "Keep the suggested copy (first)",
+            "Choose copies to keep",
+            "Skip this group",
+        ];
+        let action = Select::new()
+            .with_prompt("What should happen to this group?")
+            .default(0)
+            .items(actions)
+            .interact()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+
+        let keep: Vec<bool> = match action {
+            0 => (0..group.files.len()).map(|i| i == 0).collect(),
+            1 => {
+                let defaults: Vec<bool> = group
+                    .files
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| i == 0 || f.protected)
+                    .collect();
+                let chosen = MultiSelect::new()
+                    .with_prompt("Select copies to keep (space to toggle)")
+                    .items(&labels)
+                    .defaults(&defaults)
+                    .interact()
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+                (0..group.files.len())
+                    .map(|i| chosen.contains(&i))
+                    .collect()
+            }
+            _ => continue,
+        };
+
+        if !keep.iter().any(|k| *k) {
+            println!("⚠️ At least one copy must be kept, skipping this group.");
+            continue;
+        }
+
+        let kept_dirs: BTreeSet<PathBuf> = group
+            .files
+            .iter()
+            .zip(&keep)
+            .filter(|(_, k)| **k)
+            .map(|(f, _)| parent_dir(&f.path))
+            .collect();
+
+        let remove = group
+            .files
+            .iter()
+            .zip(&keep)
+            .filter(|(f, k)| !**k && !f.protected)
+            .map(|(f, _)| f.path.clone())
+            .collect();
+        selection.add_group(&group.files, remove);
+
+        // A choice can only be expressed per directory if it keeps or removes
+        // whole directories.
+        let consistent = group
+            .files
+            .iter()
+            .zip(&keep)
+            .all(|(f, k)| kept_dirs.contains(&parent_dir(&f.path)) == *k);
+
+        if dirs.len() > 1 && consistent && index + 1 < total {
+            let apply_all = Confirm::new()
+                .with_prompt("Apply this choice to remaining groups in the same directories?")
+                .default(false)
+                .interact()
+                .map_err(|e| io::Error::other(e.to_string()))?;
+            if apply_all {
+                remembered.insert(dirs, kept_dirs);
+            }
+        }
+    }
+
+    Ok(selection)
+}
+
+fn label(file: &FileEntry, size: Option<u64>) -> String {
+    let size = size
+        .or_else(|| std::fs::metadata(&file.path).ok().map(|m| m.len()))
+        .map(|s| format!("{:.2} MB", s as f64 / (1024.0 * 1024.0)))
+        .unwrap_or_else(|| "?".to_string());
+    let mtime = file
+        .mtime
+        .map(format_timestamp)
+        .unwrap_or_else(|| "?".to_string());
+    let protected = if file.protected { " [protected]" } else { "" };
+
+    format!(
+        "{}  ({}, modified {}){}",
+        file.path.display(),
+        size,
+        mtime,
+        protected
+    )
+}
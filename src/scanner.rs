@@ -1,8 +1,136 @@
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use std::collections::HashMap;
+use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+
+use crate::mounts;
+use crate::quarantine::QUARANTINE_DIR_NAME;
+
+/// Name of the gitignore-style files honored at every directory level.
+pub const IGNORE_FILE: &str = ".deduckignore";
+
+/// Which paths a scan skips on top of `.deduckignore` files.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    /// Gitignore-style globs for paths to skip.
+    pub excludes: Vec<String>,
+    /// Gitignore-style globs; when any are given, only matching files are
+    /// scanned.
+    pub includes: Vec<String>,
+    /// Also honor `.gitignore` files and skip `.git` directories.
+    pub gitignore: bool,
+    /// Directories never entered, such as a quarantine store.
+    pub skip_dirs: Vec<PathBuf>,
+}
+
+/// Device and inode of a file; paths with the same id are the same file.
+type FileId = (u64, u64);
+
+/// How the walk treats links and mount points.
+#[derive(Debug, Clone, Default)]
+pub struct WalkOptions {
+    /// Follow symlinks to files and directories. Symlink loops are detected
+    /// and skipped.
+    pub follow_symlinks: bool,
+    /// Never descend into a filesystem other than the root's.
+    pub one_file_system: bool,
+    /// Filesystem types whose mounts are skipped, e.g. `proc`, `sysfs` or
+    /// `fuse` (which also matches `fuse.sshfs`).
+    pub skip_fs_types: Vec<String>,
+}
+
+/// Files found by a scan. Paths naming the same file (hardlinks, or symlinks
+/// when following them) are collapsed: one of them stands for the file in
+/// `files` and the whole set is listed in `hardlinks`.
+#[derive(Debug, Clone, Default)]
+pub struct ScannedFiles {
+    pub files: Vec<PathBuf>,
+    pub hardlinks: Vec<Vec<PathBuf>>,
+    /// Every path seen, counting each name of a hardlinked file.
+    pub paths_found: usize,
+}
+
+/// Scans every root in `roots` and merges the results. Roots that repeat or
+/// lie inside another root are only walked once, and each file is listed
+/// once however many names it has.
+pub fn scan_directories(
+    roots: &[PathBuf],
+    rules: &IgnoreRules,
+    walk: &WalkOptions,
+) -> io::Result<ScannedFiles> {
+    let mut by_id: HashMap<FileId, Vec<PathBuf>> = HashMap::new();
+    let mut scanned = ScannedFiles::default();
+
+    for root in distinct_roots(roots) {
+        for (path, id) in walk_directory(&root, rules, walk)? {
+            match id {
+                Some(id) => {
+                    let paths = by_id.entry(id).or_default();
+                    if !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+                None => scanned.files.push(path),
+            }
+        }
+    }
+
+    scanned.paths_found = scanned.files.len();
+    for mut paths in by_id.into_values() {
+        paths.sort();
+        scanned.paths_found += paths.len();
+        scanned.files.push(paths[0].clone());
+        if paths.len() > 1 {
+            scanned.hardlinks.push(paths);
+        }
+    }
+    scanned.files.sort();
+    scanned.hardlinks.sort();
+
+    Ok(scanned)
+}
+
+/// `roots` without those that are the same directory as, or nested inside,
+/// another root. The remaining roots keep their order and spelling.
+pub fn distinct_roots(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let canonical: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| fs::canonicalize(root).unwrap_or_else(|_| root.clone()))
+        .collect();
+
+    roots
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| {
+            !canonical.iter().enumerate().any(|(j, other)| {
+                j != i && canonical[i].starts_with(other) && (canonical[i] != *other || j < i)
+            })
+        })
+        .map(|(_, root)| root.clone())
+        .collect()
+}
 
 pub fn scan_directory(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    scan_directory_with(dir, &IgnoreRules::default())
+}
+
+/// Lists every regular file under `dir` that is not ignored by `rules`, a
+/// `.deduckignore` file, or (with `rules.gitignore`) a `.gitignore` file.
+/// Quarantine directories are never entered.
+pub fn scan_directory_with(dir: &Path, rules: &IgnoreRules) -> io::Result<Vec<PathBuf>> {
+    let files = walk_directory(dir, rules, &WalkOptions::default())?;
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Walks `dir`, returning each regular file with its device and inode where
+/// the platform has them.
+fn walk_directory(
+    dir: &Path,
+    rules: &IgnoreRules,
+    walk: &WalkOptions,
+) -> io::Result<Vec<(PathBuf, Option<FileId>)>> {
     if !dir.exists() {
         return Err(io::Error::new(
             io::ErrorKind::NotFound,
@@ -10,16 +138,95 @@ pub fn scan_directory(dir: &Path) -> io::Result<Vec<PathBuf>> {
         ));
     }
 
-    let files: Vec<PathBuf> = WalkDir::new(dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(is_file)
-        .map(|entry| entry.path().to_path_buf())
+    let mut overrides = OverrideBuilder::new(dir);
+    for glob in &rules.includes {
+        overrides.add(glob).map_err(invalid_glob)?;
+    }
+    for glob in &rules.excludes {
+        overrides.add(&format!("!{}", glob)).map_err(invalid_glob)?;
+    }
+    let overrides = overrides.build().map_err(invalid_glob)?;
+
+    let gitignore = rules.gitignore;
+    let skipped_devices = mounts::devices_of_types(&walk.skip_fs_types);
+    let skipped_dirs: Vec<FileId> = rules
+        .skip_dirs
+        .iter()
+        .filter_map(|dir| fs::metadata(dir).ok().and_then(|m| file_id(&m)))
         .collect();
+    let walker = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .parents(true)
+        .git_ignore(gitignore)
+        .git_exclude(gitignore)
+        .require_git(false)
+        .follow_links(walk.follow_symlinks)
+        .same_file_system(walk.one_file_system)
+        .overrides(overrides)
+        .filter_entry(move |entry| {
+            let name = entry.file_name();
+            if name == QUARANTINE_DIR_NAME || (gitignore && name == ".git") {
+                return false;
+            }
+            if (skipped_devices.is_empty() && skipped_dirs.is_empty())
+                || !entry.file_type().is_some_and(|t| t.is_dir())
+            {
+                return true;
+            }
+            let Some(id) = entry.metadata().ok().and_then(|m| file_id(&m)) else {
+                return true;
+            };
+            !skipped_devices.contains(&id.0) && !skipped_dirs.contains(&id)
+        })
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                if is_loop(&e) {
+                    eprintln!("⚠️ Skipping symlink loop: {}", e);
+                }
+                continue;
+            }
+        };
+
+        // Only regular files are hashed. FIFOs, sockets and device files are
+        // skipped: reading them can block forever or never end.
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+
+        let id = entry.metadata().ok().and_then(|m| file_id(&m));
+        files.push((entry.into_path(), id));
+    }
 
     Ok(files)
 }
 
-fn is_file(entry: &walkdir::DirEntry) -> bool {
-    entry.file_type().is_file()
+fn is_loop(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<FileId> {
+    None
+}
+
+fn invalid_glob(e: ignore::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e.to_string())
 }
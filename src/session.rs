@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::duplicates::{FileEntry, ScanResult};
+use crate::hasher::{self, HashAlgorithm};
+use crate::options::ScanMode;
+use crate::quarantine::unix_now;
+
+/// The result of a `filter` run, saved so `clean` acts on exactly what the
+/// user saw.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub roots: Vec<PathBuf>,
+    pub mode: ScanMode,
+    /// Unix seconds when the scan finished.
+    pub created_at: u64,
+    pub result: ScanResult,
+}
+
+/// A file left alone because it no longer matches the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// The group a file was found in: all of its copies and, for exact
+/// duplicates, the content hash every copy must still have.
+struct Origin<'a> {
+    entry: &'a FileEntry,
+    copies: Vec<&'a FileEntry>,
+    hash: Option<(&'a str, &'a str)>,
+}
+
+impl Session {
+    /// Records `result` with every path made absolute, so `clean` acts on
+    /// the same files whatever directory it runs from.
+    pub fn new(roots: &[PathBuf], mode: ScanMode, mut result: ScanResult) -> Self {
+        for group in &mut result.duplicates {
+            for entry in &mut group.files {
+                make_absolute(&mut entry.path);
+            }
+        }
+        for group in &mut result.similar {
+            make_absolute(&mut group.base.path);
+            for entry in &mut group.similar {
+                make_absolute(&mut entry.path);
+            }
+        }
+        for group in &mut result.hardlinks {
+            group.files.iter_mut().for_each(make_absolute);
+        }
+
+        Session {
+            roots: normalize(roots),
+            mode,
+            created_at: unix_now(),
+            result,
+        }
+    }
+
+    /// Whether the session was recorded for the same set of roots, in any
+    /// order.
+    pub fn is_for(&self, roots: &[PathBuf]) -> bool {
+        self.roots == normalize(roots)
+    }
+
+    /// Splits `paths` into files that still match what the session recorded
+    /// and files that must be skipped. A copy is only kept in the result if
+    /// its size, mtime and hash are unchanged and the same holds for every
+    /// copy of its group in `kept`, the copies that survive in its place.
+    pub fn verify(
+        &self,
+        paths: Vec<PathBuf>,
+        kept: &HashSet<PathBuf>,
+    ) -> (Vec<PathBuf>, Vec<Skipped>) {
+        let origins = self.origins();
+        let mut checked: HashMap<&Path, Result<(), String>> = HashMap::new();
+        let mut verified = Vec::new();
+        let mut skipped = Vec::new();
+
+        for path in paths {
+            let Some(origin) = origins.get(path.as_path()) else {
+                skipped.push(Skipped {
+                    reason: "not part of the last filter run".to_string(),
+                    path,
+                });
+                continue;
+            };
+
+            let survivors: Vec<&FileEntry> = origin
+                .copies
+                .iter()
+                .copied()
+                .filter(|copy| copy.path != path && kept.contains(&copy.path))
+                .collect();
+            if survivors.is_empty() {
+                skipped.push(Skipped {
+                    reason: "no copy of it is kept".to_string(),
+                    path,
+                });
+                continue;
+            }
+
+            let survivors_ok = survivors.iter().try_for_each(|copy| {
+                checked
+                    .entry(copy.path.as_path())
+                    .or_insert_with(|| check(copy, origin.hash))
+                    .clone()
+                    .map_err(|reason| format!("kept copy {} {}", copy.path.display(), reason))
+            });
+
+            match survivors_ok.and_then(|_| check(origin.entry, origin.hash)) {
+                Ok(()) => verified.push(path),
+                Err(reason) => skipped.push(Skipped { path, reason }),
+            }
+        }
+
+        (verified, skipped)
+    }
+
+    fn origins(&self) -> HashMap<&Path, Origin<'_>> {
+        let mut origins = HashMap::new();
+
+        for group in &self.result.duplicates {
+            let copies: Vec<&FileEntry> = group.files.iter().collect();
+            for entry in &group.files {
+                origins.insert(
+                    entry.path.as_path(),
+                    Origin {
+                        entry,
+                        copies: copies.clone(),
+                        hash: Some((group.algorithm.as_str(), group.hash.as_str())),
+                    },
+                );
+            }
+        }
+
+        for group in &self.result.similar {
+            let copies: Vec<&FileEntry> =
+                std::iter::once(&group.base).chain(&group.similar).collect();
+            for entry in &copies {
+                origins.insert(
+                    entry.path.as_path(),
+                    Origin {
+                        entry,
+                        copies: copies.clone(),
+                        hash: None,
+                    },
+                );
+            }
+        }
+
+        origins
+    }
+}
+
+fn check(entry: &FileEntry, hash: Option<(&str, &str)>) -> Result<(), String> {
+    let metadata = fs::metadata(&entry.path).map_err(|_| "no longer exists".to_string())?;
+
+    if metadata.len() != entry.size {
+        return Err("changed size".to_string());
+    }
+
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    if mtime != entry.mtime {
+        return Err("was modified".to_string());
+    }
+
+    if let Some((algorithm, expected)) = hash {
+        let algo = HashAlgorithm::from_name(algorithm)
+            .ok_or_else(|| format!("has unknown hash algorithm {}", algorithm))?;
+        let actual = hasher::hash_file(&entry.path, &algo).map_err(|e| e.to_string())?;
+        if actual != expected {
+            return Err("changed content".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Joins a relative path onto the working directory without resolving
+/// symlinks, so the path still names the entry that was scanned.
+fn make_absolute(path: &mut PathBuf) {
+    if let Ok(absolute) = std::path::absolute(&*path) {
+        *path = absolute;
+    }
+}
+
+fn normalize(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| fs::canonicalize(root).unwrap_or_else(|_| root.clone()))
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
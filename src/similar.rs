@@ -3,6 +3,9 @@ use imagehash::perceptual_hash;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+/// Image formats the similarity scan is able to decode.
+pub const SIMILARITY_EXTS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff"];
+
 pub fn similar_images(files: Vec<PathBuf>, threshold: u32) -> HashMap<PathBuf, Vec<PathBuf>> {
     let mut hashes = Vec::new();
 
@@ -36,6 +39,6 @@ pub fn similar_images(files: Vec<PathBuf>, threshold: u32) -> HashMap<PathBuf, V
     groups
 }
 
-fn hamming_distance(a: &Vec<bool>, b: &Vec<bool>) -> u32 {
+fn hamming_distance(a: &[bool], b: &[bool]) -> u32 {
     a.iter().zip(b.iter()).filter(|(x, y)| x != y).count() as u32
 }
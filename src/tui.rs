@@ -0,0 +1,411 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::path::PathBuf;
+
+use crate::duplicates::{FileEntry, ScanResult};
+use crate::utils::format_timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Keep,
+    Remove,
+}
+
+pub struct TuiFile {
+    pub entry: FileEntry,
+    pub size: u64,
+    pub mark: Mark,
+}
+
+pub struct TuiGroup {
+    pub title: String,
+    pub files: Vec<TuiFile>,
+}
+
+impl TuiGroup {
+    pub fn reclaimable(&self) -> u64 {
+        self.files
+            .iter()
+            .filter(|f| f.mark == Mark::Remove)
+            .map(|f| f.size)
+            .sum()
+    }
+
+    fn matches(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.files.iter().any(|f| {
+                f.entry
+                    .path
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(filter)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Groups,
+    Files,
+    Filter,
+    Confirm,
+}
+
+/// What the browser ended with.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Quit,
+    Quarantine(Vec<PathBuf>),
+}
+
+/// State of the duplicate browser, independent of the terminal so key
+/// handling can be exercised directly.
+pub struct App {
+    pub groups: Vec<TuiGroup>,
+    pub visible: Vec<usize>,
+    pub filter: String,
+    pub focus: Focus,
+    pub group_cursor: usize,
+    pub file_cursor: usize,
+}
+
+impl App {
+    pub fn new(result: &ScanResult) -> Self {
+        let mut groups = Vec::new();
+
+        for group in &result.duplicates {
+            groups.push(TuiGroup {
+                title: format!("{} {}", group.algorithm, group.hash),
+                files: group
+                    .files
+                    .iter()
+                    .enumerate()
+                    .map(|(i, f)| tui_file(f, group.size, i))
+                    .collect(),
+            });
+        }
+
+        for group in &result.similar {
+            let files = std::iter::once(&group.base).chain(group.similar.iter());
+            groups.push(TuiGroup {
+                title: "similar images".to_string(),
+                files: files
+                    .enumerate()
+                    .map(|(i, f)| tui_file(f, f.size, i))
+                    .collect(),
+            });
+        }
+
+        groups.sort_by_key(|g| std::cmp::Reverse(g.reclaimable()));
+
+        let mut app = App {
+            groups,
+            visible: Vec::new(),
+            filter: String::new(),
+            focus: Focus::Groups,
+            group_cursor: 0,
+            file_cursor: 0,
+        };
+        app.refresh_visible();
+        app
+    }
+
+    pub fn selected_group(&self) -> Option<&TuiGroup> {
+        self.visible
+            .get(self.group_cursor)
+            .map(|&index| &self.groups[index])
+    }
+
+    /// Files marked for removal across all groups, filtered or not.
+    pub fn marked_files(&self) -> Vec<PathBuf> {
+        self.groups
+            .iter()
+            .flat_map(|g| g.files.iter())
+            .filter(|f| f.mark == Mark::Remove)
+            .map(|f| f.entry.path.clone())
+            .collect()
+    }
+
+    pub fn marked_bytes(&self) -> u64 {
+        self.groups.iter().map(TuiGroup::reclaimable).sum()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        match self.focus {
+            Focus::Groups => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Some(Outcome::Quit),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.group_cursor = self.group_cursor.saturating_sub(1);
+                    self.file_cursor = 0;
+                }
+                KeyCode::Down | KeyCode::Char('j')
+                    if self.group_cursor + 1 < self.visible.len() =>
+                {
+                    self.group_cursor += 1;
+                    self.file_cursor = 0;
+                }
+                KeyCode::Enter | KeyCode::Right | KeyCode::Tab
+                    if self.selected_group().is_some() =>
+                {
+                    self.focus = Focus::Files;
+                }
+                KeyCode::Char('/') => self.focus = Focus::Filter,
+                KeyCode::Char('c') => self.focus = Focus::Confirm,
+                _ => {}
+            },
+            Focus::Files => match key.code {
+                KeyCode::Esc | KeyCode::Left | KeyCode::Tab => self.focus = Focus::Groups,
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.file_cursor = self.file_cursor.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let len = self.selected_group().map_or(0, |g| g.files.len());
+                    if self.file_cursor + 1 < len {
+                        self.file_cursor += 1;
+                    }
+                }
+                KeyCode::Char(' ') => self.toggle_mark(),
+                KeyCode::Char('c') => self.focus = Focus::Confirm,
+                _ => {}
+            },
+            Focus::Filter => match key.code {
+                KeyCode::Enter => self.focus = Focus::Groups,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.refresh_visible();
+                    self.focus = Focus::Groups;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.refresh_visible();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.refresh_visible();
+                }
+                _ => {}
+            },
+            Focus::Confirm => match key.code {
+                KeyCode::Char('y') => return Some(Outcome::Quarantine(self.marked_files())),
+                KeyCode::Char('n') | KeyCode::Esc => self.focus = Focus::Groups,
+                _ => {}
+            },
+        }
+
+        None
+    }
+
+    /// Flips the mark of the file under the cursor. Protected files and the
+    /// last kept copy of a group cannot be marked for removal.
+    fn toggle_mark(&mut self) {
+        let Some(&index) = self.visible.get(self.group_cursor) else {
+            return;
+        };
+        let group = &mut self.groups[index];
+        let kept = group.files.iter().filter(|f| f.mark == Mark::Keep).count();
+
+        let Some(file) = group.files.get_mut(self.file_cursor) else {
+            return;
+        };
+        file.mark = match file.mark {
+            Mark::Remove => Mark::Keep,
+            Mark::Keep if file.entry.protected || kept <= 1 => Mark::Keep,
+            Mark::Keep => Mark::Remove,
+        };
+    }
+
+    fn refresh_visible(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.visible = (0..self.groups.len())
+            .filter(|&i| self.groups[i].matches(&filter))
+            .collect();
+        self.group_cursor = self.group_cursor.min(self.visible.len().saturating_sub(1));
+        self.file_cursor = 0;
+    }
+}
+
+fn tui_file(entry: &FileEntry, size: u64, index: usize) -> TuiFile {
+    let mark = if index == 0 || entry.protected {
+        Mark::Keep
+    } else {
+        Mark::Remove
+    };
+    TuiFile {
+        entry: entry.clone(),
+        size,
+        mark,
+    }
+}
+
+/// Runs the browser until the user quits or confirms, restoring the
+/// terminal afterwards.
+pub fn run(result: &ScanResult) -> io::Result<Outcome> {
+    let mut app = App::new(result);
+    let mut terminal = ratatui::try_init()?;
+    let outcome = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    outcome
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<Outcome> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Some(outcome) = app.handle_key(key)
+        {
+            return Ok(outcome);
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [left, right] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+
+    let highlight = Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD);
+
+    let items: Vec<ListItem> = app
+        .visible
+        .iter()
+        .map(|&i| {
+            let group = &app.groups[i];
+            let first = group
+                .files
+                .first()
+                .map(|f| f.entry.path.display().to_string())
+                .unwrap_or_default();
+            ListItem::new(format!(
+                "{:>10}  {:>3} files  {}",
+                format_mb(group.reclaimable()),
+                group.files.len(),
+                first
+            ))
+        })
+        .collect();
+
+    let groups_title = format!(
+        " Duplicate groups ({}/{}) ",
+        app.visible.len(),
+        app.groups.len()
+    );
+    let mut group_state = ListState::default().with_selected(Some(app.group_cursor));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(bordered(&groups_title, app.focus == Focus::Groups))
+            .highlight_style(highlight),
+        left,
+        &mut group_state,
+    );
+
+    let (detail_title, file_items) = match app.selected_group() {
+        Some(group) => (
+            format!(" {} ", group.title),
+            group
+                .files
+                .iter()
+                .map(|f| {
+                    let (tag, color) = match f.mark {
+                        Mark::Keep => ("KEEP  ", Color::Green),
+                        Mark::Remove => ("REMOVE", Color::Red),
+                    };
+                    let mtime = f.entry.mtime.map(format_timestamp).unwrap_or_default();
+                    let protected = if f.entry.protected {
+                        " [protected]"
+                    } else {
+                        ""
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(tag, Style::default().fg(color)),
+                        Span::raw(format!(
+                            "  {}  {}  {}{}",
+                            format_mb(f.size),
+                            mtime,
+                            f.entry.path.display(),
+                            protected
+                        )),
+                    ]))
+                })
+                .collect(),
+        ),
+        None => (" No groups ".to_string(), Vec::new()),
+    };
+
+    let mut file_state =
+        ListState::default().with_selected((app.focus == Focus::Files).then_some(app.file_cursor));
+    frame.render_stateful_widget(
+        List::new(file_items)
+            .block(bordered(&detail_title, app.focus == Focus::Files))
+            .highlight_style(highlight),
+        right,
+        &mut file_state,
+    );
+
+    let status_text = match app.focus {
+        Focus::Filter => format!("/{}", app.filter),
+        _ => format!(
+            "↑↓ move  ⏎ open  space mark  / filter{}  c confirm  q quit   marked: {} files, {}",
+            if app.filter.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", app.filter)
+            },
+            app.marked_files().len(),
+            format_mb(app.marked_bytes())
+        ),
+    };
+    frame.render_widget(Paragraph::new(status_text), status);
+
+    if app.focus == Focus::Confirm {
+        let area = centered(frame.area(), 60, 7);
+        let text = format!(
+            "Quarantine {} marked files ({})?\n\n[y] yes   [n] back",
+            app.marked_files().len(),
+            format_mb(app.marked_bytes())
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: true })
+                .block(bordered(" Confirm ", true)),
+            area,
+        );
+    }
+}
+
+fn bordered(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+}
@@ -1,3 +1,4 @@
+use crate::quarantine::{MANIFEST_FILE, Manifest};
 use crate::report::Report;
 use std::fs;
 use std::io;
@@ -8,10 +9,25 @@ pub fn process_quarantined_files(quarantine_dir: &Path, report: &mut Report) ->
         return Ok(());
     }
 
+    let manifest = Manifest::load(quarantine_dir)?;
+    for entry in &manifest.entries {
+        report.add_file(entry.original_path.clone(), entry.size);
+    }
+
     let entries = fs::read_dir(quarantine_dir)?;
     for entry in entries {
         let entry = entry?;
         let path = entry.path();
+        let name = entry.file_name();
+
+        if name == MANIFEST_FILE
+            || manifest
+                .entries
+                .iter()
+                .any(|e| e.stored_name.as_str() == name)
+        {
+            continue;
+        }
 
         if let Ok(metadata) = fs::metadata(&path) {
             report.add_file(path, metadata.len());
@@ -24,7 +40,74 @@ pub fn process_quarantined_files(quarantine_dir: &Path, report: &mut Report) ->
 pub fn delete_quarantine_dir(path: &Path) -> io::Result<()> {
     if path.exists() {
         fs::remove_dir_all(path)?;
-        println!("🗑️ Quarantine folder deleted: {}", path.display());
+        eprintln!("🗑️ Quarantine folder deleted: {}", path.display());
     }
     Ok(())
 }
+
+/// Formats a duration in seconds in its largest whole unit, e.g. `3d`.
+pub fn format_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3_600 => format!("{}m", secs / 60),
+        3_600..86_400 => format!("{}h", secs / 3_600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
+/// Parses a duration such as `90m`, `12h`, `30d` or `2w` into seconds. The
+/// unit is required so a bare number is never mistaken for days.
+pub fn parse_duration(input: &str) -> Result<u64, String> {
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration `{}`", input))?;
+    let seconds = match unit.trim().to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in `{}` (expected s, m, h, d or w)",
+                input
+            ));
+        }
+    };
+
+    number
+        .checked_mul(seconds)
+        .ok_or_else(|| format!("duration `{}` is too long", input))
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let minutes = (secs % 86_400) / 60;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        minutes / 60,
+        minutes % 60
+    )
+}
@@ -0,0 +1,119 @@
+use deduck::cache::{Fingerprint, HashCache};
+use deduck::duplicates::find_duplicates;
+use deduck::options::ScanOptions;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_cached_hash_reused_until_file_changes() {
+    let temp = tempdir().unwrap();
+    let file = temp.path().join("a.txt");
+    fs::write(&file, b"first").unwrap();
+
+    let cache = HashCache::default();
+    let hash = cache
+        .get_or_compute(&file, "blake3", || Ok("h1".to_string()))
+        .unwrap();
+    assert_eq!(hash, "h1");
+
+    let hash = cache
+        .get_or_compute(&file, "blake3", || panic!("unchanged file was rehashed"))
+        .unwrap();
+    assert_eq!(hash, "h1");
+
+    // Other algorithms are cached separately.
+    let hash = cache
+        .get_or_compute(&file, "sha256", || Ok("s1".to_string()))
+        .unwrap();
+    assert_eq!(hash, "s1");
+
+    fs::write(&file, b"second, longer").unwrap();
+    let hash = cache
+        .get_or_compute(&file, "blake3", || Ok("h2".to_string()))
+        .unwrap();
+    assert_eq!(hash, "h2");
+
+    let fingerprint = Fingerprint::of(&file).unwrap();
+    assert_eq!(cache.get(&file, &fingerprint, "sha256"), None);
+}
+
+#[test]
+fn test_cache_persists_and_drops_missing_files() {
+    let temp = tempdir().unwrap();
+    let kept = temp.path().join("kept.txt");
+    let gone = temp.path().join("gone.txt");
+    fs::write(&kept, b"kept").unwrap();
+    fs::write(&gone, b"gone").unwrap();
+
+    let cache = HashCache::default();
+    for file in [&kept, &gone] {
+        cache
+            .get_or_compute(file, "blake3", || Ok("hash".to_string()))
+            .unwrap();
+    }
+    fs::remove_file(&gone).unwrap();
+
+    // Entries outside the scanned roots are never pruned.
+    let elsewhere = tempdir().unwrap();
+    let other = elsewhere.path().join("other.txt");
+    fs::write(&other, b"other").unwrap();
+    cache
+        .get_or_compute(&other, "blake3", || Ok("hash".to_string()))
+        .unwrap();
+    fs::remove_file(&other).unwrap();
+
+    let found: HashSet<PathBuf> = [kept.clone()].into_iter().collect();
+    cache.prune(&[temp.path().to_path_buf()], &found);
+
+    let cache_file = temp.path().join("cache").join("hashes.json");
+    cache.save(&cache_file).unwrap();
+    assert_eq!(
+        fs::read_dir(cache_file.parent().unwrap()).unwrap().count(),
+        1
+    );
+
+    let loaded = HashCache::load(&cache_file);
+    assert_eq!(loaded.len(), 2);
+    let fingerprint = Fingerprint::of(&kept).unwrap();
+    assert_eq!(
+        loaded.get(&kept, &fingerprint, "blake3"),
+        Some("hash".to_string())
+    );
+}
+
+#[test]
+fn test_scan_with_cache_file_matches_uncached_scan() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("root");
+    fs::create_dir(&root).unwrap();
+    fs::write(root.join("a.txt"), b"same").unwrap();
+    fs::write(root.join("b.txt"), b"same").unwrap();
+    fs::write(root.join("c.txt"), b"diff").unwrap();
+
+    let cache_file = temp.path().join("hash_cache.json");
+    let options = ScanOptions {
+        cache_file: Some(cache_file.clone()),
+        ..ScanOptions::new(&root)
+    };
+
+    let first = find_duplicates(&options).unwrap();
+    assert!(cache_file.exists());
+    assert_eq!(HashCache::load(&cache_file).len(), 3);
+
+    let second = find_duplicates(&options).unwrap();
+    let uncached = find_duplicates(&ScanOptions::new(&root)).unwrap();
+
+    for result in [&second, &uncached] {
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(result.duplicates[0].hash, first.duplicates[0].hash);
+    }
+
+    // Files the next scan no longer finds are forgotten.
+    fs::write(root.join("d.txt"), b"diff").unwrap();
+    fs::remove_file(root.join("a.txt")).unwrap();
+    find_duplicates(&options).unwrap();
+    let cache = HashCache::load(&cache_file);
+    assert_eq!(cache.len(), 3);
+}
@@ -1,4 +1,7 @@
-use deduck::duplicates::duplicates;
+use deduck::duplicates::find_duplicates;
+use deduck::options::{ScanMode, ScanOptions};
+use deduck::quarantine::{Manifest, get_quarantine_dir, quarantine_duplicates};
+use deduck::report::Report;
 use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::Write;
@@ -24,16 +27,31 @@ fn test_find_and_process_duplicates_with_quarantine() {
     create_dummy_file(search_dir, "file5.png", b"same image");
 
     // Run with quarantine enabled
-    let result = duplicates(search_dir, 1, true);
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 5);
+    let result = find_duplicates(&ScanOptions::new(search_dir)).unwrap();
+    assert_eq!(result.files_found, 5);
+    assert_eq!(result.duplicates.len(), 2);
+
+    quarantine_duplicates(
+        result.redundant_files(),
+        &get_quarantine_dir(search_dir),
+        &mut Report::new(),
+    )
+    .unwrap();
 
     let quarantine_dir = search_dir.join(".deduck_quarantine");
     assert!(quarantine_dir.exists());
 
-    let quarantined: HashSet<_> = fs::read_dir(&quarantine_dir)
+    let quarantined: HashSet<_> = Manifest::load(&quarantine_dir)
         .unwrap()
-        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .entries
+        .iter()
+        .map(|e| {
+            e.original_path
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        })
         .collect();
 
     let remaining: HashSet<_> = fs::read_dir(search_dir)
@@ -115,8 +133,9 @@ fn test_find_and_process_duplicates_no_quarantine() {
     create_dummy_file(search_dir, "file1.txt", b"duplicate content");
     create_dummy_file(search_dir, "file2.txt", b"duplicate content");
 
-    let result = duplicates(search_dir, 1, false);
-    assert!(result.is_ok());
+    let result = find_duplicates(&ScanOptions::new(search_dir)).unwrap();
+    assert_eq!(result.duplicates.len(), 1);
+    assert_eq!(result.duplicates[0].files.len(), 2);
 
     let quarantine_dir = search_dir.join(".deduck_quarantine");
     assert!(!quarantine_dir.exists());
@@ -124,3 +143,81 @@ fn test_find_and_process_duplicates_no_quarantine() {
     assert!(search_dir.join("file1.txt").exists());
     assert!(search_dir.join("file2.txt").exists());
 }
+
+#[test]
+fn test_find_duplicates_quick_mode_reports_algorithm() {
+    let temp_dir = tempdir().unwrap();
+    let search_dir = temp_dir.path();
+
+    create_dummy_file(search_dir, "a.bin", b"payload");
+    create_dummy_file(search_dir, "b.bin", b"payload");
+    create_dummy_file(search_dir, "c.bin", b"payloaX");
+
+    let options = ScanOptions {
+        mode: ScanMode::Quick,
+        ..ScanOptions::new(search_dir)
+    };
+    let result = find_duplicates(&options).unwrap();
+
+    assert_eq!(result.duplicates.len(), 1);
+    let group = &result.duplicates[0];
+    assert_eq!(group.algorithm, "xxhash64");
+    assert_eq!(group.size, 7);
+    assert_eq!(result.redundant_files(), vec![search_dir.join("b.bin")]);
+}
+
+#[test]
+fn test_reference_directory_is_kept_and_never_quarantined() {
+    let temp_dir = tempdir().unwrap();
+    let incoming = temp_dir.path().join("incoming");
+    let archive = temp_dir.path().join("archive");
+    fs::create_dir_all(&incoming).unwrap();
+    fs::create_dir_all(&archive).unwrap();
+
+    create_dummy_file(&archive, "a.jpg", b"archived photo");
+    create_dummy_file(&archive, "a-copy.jpg", b"archived photo");
+    create_dummy_file(&incoming, "z.jpg", b"archived photo");
+    // Duplicated only within incoming, and only within archive.
+    create_dummy_file(&incoming, "new1.txt", b"new file");
+    create_dummy_file(&incoming, "new2.txt", b"new file");
+    create_dummy_file(&archive, "old1.txt", b"old file");
+    create_dummy_file(&archive, "old2.txt", b"old file");
+
+    let options = ScanOptions {
+        references: vec![archive.clone()],
+        ..ScanOptions::new(&incoming)
+    };
+    let result = find_duplicates(&options).unwrap();
+
+    assert_eq!(result.files_found, 7);
+    assert_eq!(result.duplicates.len(), 1);
+    let group = &result.duplicates[0];
+    assert!(group.files[0].path.starts_with(&archive));
+    assert!(group.files[0].protected);
+    assert_eq!(result.redundant_files(), vec![incoming.join("z.jpg")]);
+}
+
+#[test]
+fn test_hardlinks_are_reported_apart_from_duplicates() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+
+    let original = create_dummy_file(root, "a.txt", b"linked content");
+    fs::hard_link(&original, root.join("b.txt")).unwrap();
+
+    let result = find_duplicates(&ScanOptions::new(root)).unwrap();
+    assert_eq!(result.files_found, 2);
+    assert!(result.duplicates.is_empty());
+    assert_eq!(result.hardlinks.len(), 1);
+    assert_eq!(result.hardlinks[0].size, 14);
+    assert_eq!(
+        result.hardlinks[0].files,
+        vec![original, root.join("b.txt")]
+    );
+
+    // A real copy is a duplicate of the hardlinked file, counted once.
+    create_dummy_file(root, "c.txt", b"linked content");
+    let result = find_duplicates(&ScanOptions::new(root)).unwrap();
+    assert_eq!(result.duplicates.len(), 1);
+    assert_eq!(result.duplicates[0].files.len(), 2);
+}
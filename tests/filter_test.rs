@@ -1,4 +1,4 @@
-use deduck::filters::batch;
+use deduck::filters::{ExtensionFilter, SizeFilter, batch, parse_size};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
@@ -26,9 +26,9 @@ fn test_batch() {
     create_dummy_file(&file4, 1_000_000);
 
     let all_files = vec![file1, file2, file3, file4];
-    let allowed_exts = ["pdf", "png"];
+    let filter = ExtensionFilter::include(&["pdf", "png"]);
 
-    let result = batch(all_files, &allowed_exts).unwrap();
+    let result = batch(all_files, &filter, &SizeFilter::default()).unwrap();
 
     assert_eq!(result.len(), 2);
 
@@ -38,5 +38,82 @@ fn test_batch() {
     let png_group = result.get(&("png".to_string(), 1_000_000)).unwrap();
     assert_eq!(png_group.len(), 1);
 
-    assert!(result.get(&("txt".to_string(), 1_000_000)).is_none());
+    assert!(!result.contains_key(&("txt".to_string(), 1_000_000)));
+}
+
+#[test]
+fn test_batch_defaults_to_all_files() {
+    let temp = tempfile::tempdir().unwrap();
+    let dir_path = temp.path();
+
+    let video = dir_path.join("clip.MP4");
+    let archive = dir_path.join("backup.zip");
+    let no_ext = dir_path.join("Makefile");
+    for file in [&video, &archive, &no_ext] {
+        create_dummy_file(file, 10);
+    }
+
+    let all_files = vec![video.clone(), archive.clone(), no_ext.clone()];
+
+    let result = batch(
+        all_files.clone(),
+        &ExtensionFilter::default(),
+        &SizeFilter::default(),
+    )
+    .unwrap();
+    assert_eq!(result.len(), 3);
+    assert!(result.contains_key(&("mp4".to_string(), 10)));
+    assert!(result.contains_key(&("".to_string(), 10)));
+
+    let filter = ExtensionFilter::new(&[], &[".ZIP".to_string()], &["video".to_string()]);
+    let result = batch(all_files, &filter, &SizeFilter::default()).unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[&("mp4".to_string(), 10)], vec![video]);
+}
+
+#[test]
+fn test_size_filter_and_units() {
+    assert_eq!(parse_size("512"), Ok(512));
+    assert_eq!(parse_size("10K"), Ok(10 * 1024));
+    assert_eq!(parse_size("1.5m"), Ok(1024 * 1024 * 3 / 2));
+    assert_eq!(parse_size("2GiB"), Ok(2 * 1024 * 1024 * 1024));
+    assert!(parse_size("ten").is_err());
+    assert!(parse_size("5X").is_err());
+
+    let temp = tempfile::tempdir().unwrap();
+    let files: Vec<PathBuf> = [0, 0, 100, 100, 5000, 5000]
+        .iter()
+        .enumerate()
+        .map(|(i, size)| {
+            let path = temp.path().join(format!("{}.bin", i));
+            create_dummy_file(&path, *size);
+            path
+        })
+        .collect();
+
+    let result = batch(
+        files.clone(),
+        &ExtensionFilter::default(),
+        &SizeFilter::default(),
+    )
+    .unwrap();
+    assert!(!result.contains_key(&("bin".to_string(), 0)));
+    assert_eq!(result.len(), 2);
+
+    let size_filter = SizeFilter {
+        max: Some(parse_size("1K").unwrap()),
+        include_empty: true,
+        ..SizeFilter::default()
+    };
+    let result = batch(files.clone(), &ExtensionFilter::default(), &size_filter).unwrap();
+    assert_eq!(result[&("bin".to_string(), 0)].len(), 2);
+    assert!(!result.contains_key(&("bin".to_string(), 5000)));
+
+    let size_filter = SizeFilter {
+        min: Some(200),
+        ..SizeFilter::default()
+    };
+    let result = batch(files, &ExtensionFilter::default(), &size_filter).unwrap();
+    assert_eq!(result.len(), 1);
+    assert!(result.contains_key(&("bin".to_string(), 5000)));
 }
@@ -2,7 +2,7 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::path::PathBuf;
 
-use deduck::hasher::{HashAlgorithm, hash_files};
+use deduck::hasher::{HashAlgorithm, PARTIAL_HASH_BYTES, hash_files, hash_files_staged};
 use indicatif::{ProgressBar, ProgressStyle};
 
 fn create_temp_file(content: &str, filename: &str) -> PathBuf {
@@ -46,3 +46,34 @@ fn test_hash_files_group_duplicates_with_progress() {
         }
     }
 }
+
+#[test]
+fn test_hash_files_staged_only_groups_full_matches() {
+    let temp = tempfile::tempdir().unwrap();
+    let size = (PARTIAL_HASH_BYTES * 4) as usize;
+
+    // Same head and tail, different middle: partial hashes collide.
+    let mut middle_a = vec![7u8; size];
+    let mut middle_b = vec![7u8; size];
+    middle_a[size / 2] = 1;
+    middle_b[size / 2] = 2;
+
+    let a = temp.path().join("a.bin");
+    let b = temp.path().join("b.bin");
+    let c = temp.path().join("c.bin");
+    let d = temp.path().join("d.bin");
+    fs::write(&a, &middle_a).unwrap();
+    fs::write(&b, &middle_b).unwrap();
+    fs::write(&c, &middle_a).unwrap();
+    fs::write(&d, vec![9u8; size]).unwrap();
+
+    let files = vec![a.clone(), b.clone(), c.clone(), d.clone()];
+    let hash_map = hash_files_staged(files, HashAlgorithm::Blake3, ProgressBar::hidden());
+
+    let groups: Vec<_> = hash_map.values().filter(|g| g.len() > 1).collect();
+    assert_eq!(groups.len(), 1);
+    assert!(groups[0].contains(&a) && groups[0].contains(&c));
+
+    // `d` has a unique partial hash and is never fully hashed.
+    assert!(hash_map.values().all(|g| !g.contains(&d)));
+}
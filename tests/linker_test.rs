@@ -0,0 +1,203 @@
+use deduck::duplicates::DuplicateGroup;
+use deduck::linker::{
+    files_identical, hardlink_duplicates, relative_path, symlink_duplicates, undo_link,
+};
+use deduck::report::{LinkKind, LinkRecord, Report};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+#[test]
+fn test_hardlink_duplicates_keeps_paths_valid() {
+    let temp_dir = tempdir().unwrap();
+    let keep = temp_dir.path().join("a.bin");
+    let copy = temp_dir.path().join("b.bin");
+    let other = temp_dir.path().join("c.bin");
+    fs::write(&keep, b"shared data").unwrap();
+    fs::write(&copy, b"shared data").unwrap();
+    fs::write(&other, b"shared DATA").unwrap();
+
+    assert!(files_identical(&keep, &copy).unwrap());
+    assert!(!files_identical(&keep, &other).unwrap());
+
+    // `c.bin` claims the same hash but differs, so it must be left alone.
+    let group = DuplicateGroup::new(
+        "hash".to_string(),
+        "blake3",
+        vec![keep.clone(), copy.clone(), other.clone()],
+    );
+
+    let mut report = Report::new();
+    hardlink_duplicates(&[group], &mut report).unwrap();
+
+    assert_eq!(report.links.len(), 1);
+    assert_eq!(report.links[0].path, copy);
+    assert_eq!(report.links[0].target, keep);
+    assert_eq!(report.links[0].kind, LinkKind::Hardlink);
+    assert_eq!(report.space_freed, 11);
+
+    assert_eq!(fs::read(&copy).unwrap(), b"shared data");
+    assert_eq!(fs::read(&other).unwrap(), b"shared DATA");
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 3);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let keep_meta = fs::metadata(&keep).unwrap();
+        assert_eq!(keep_meta.ino(), fs::metadata(&copy).unwrap().ino());
+        assert_ne!(keep_meta.ino(), fs::metadata(&other).unwrap().ino());
+        assert_eq!(keep_meta.nlink(), 2);
+    }
+}
+
+#[test]
+fn test_hardlink_dry_run_changes_nothing() {
+    let temp_dir = tempdir().unwrap();
+    let keep = temp_dir.path().join("a.bin");
+    let copy = temp_dir.path().join("b.bin");
+    fs::write(&keep, b"shared data").unwrap();
+    fs::write(&copy, b"shared data").unwrap();
+
+    let group = DuplicateGroup::new("hash".to_string(), "blake3", vec![keep, copy.clone()]);
+
+    let mut report = Report::dry_run();
+    hardlink_duplicates(&[group], &mut report).unwrap();
+
+    assert_eq!(report.links.len(), 1);
+    assert_eq!(report.links[0].path, copy);
+    assert_eq!(report.space_freed, 11);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(fs::metadata(&copy).unwrap().nlink(), 1);
+    }
+}
+
+#[test]
+fn test_relative_path() {
+    assert_eq!(
+        relative_path(Path::new("/a/b/c"), Path::new("/a/d/e.txt")),
+        PathBuf::from("../../d/e.txt")
+    );
+    assert_eq!(
+        relative_path(Path::new("/a"), Path::new("/a/e.txt")),
+        PathBuf::from("e.txt")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlink_duplicates_and_undo() {
+    let temp_dir = tempdir().unwrap();
+    let root = fs::canonicalize(temp_dir.path()).unwrap();
+    let nested = root.join("web").join("icons");
+    fs::create_dir_all(&nested).unwrap();
+
+    let keep = root.join("logo.png");
+    let copy = nested.join("logo.png");
+    fs::write(&keep, b"png bytes").unwrap();
+    fs::write(&copy, b"png bytes").unwrap();
+
+    let group = DuplicateGroup::new(
+        "hash".to_string(),
+        "blake3",
+        vec![keep.clone(), copy.clone()],
+    );
+    let mut report = Report::new();
+    symlink_duplicates(&[group], &mut report).unwrap();
+
+    assert_eq!(report.links.len(), 1);
+    assert_eq!(report.links[0].kind, LinkKind::Symlink);
+    assert_eq!(report.links[0].target, keep);
+    assert!(
+        fs::symlink_metadata(&copy)
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+    assert_eq!(
+        fs::read_link(&copy).unwrap(),
+        PathBuf::from("../../logo.png")
+    );
+    assert_eq!(fs::read(&copy).unwrap(), b"png bytes");
+
+    undo_link(&report.links[0]).unwrap();
+    assert!(
+        !fs::symlink_metadata(&copy)
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+    assert_eq!(fs::read(&copy).unwrap(), b"png bytes");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_undo_link_never_overwrites_later_edits() {
+    let temp_dir = tempdir().unwrap();
+    let keep = temp_dir.path().join("a.txt");
+    let linked = temp_dir.path().join("b.txt");
+    let edited = temp_dir.path().join("c.txt");
+    fs::write(&keep, b"original").unwrap();
+    fs::write(&linked, b"original").unwrap();
+    fs::write(&edited, b"original").unwrap();
+
+    let group = DuplicateGroup::new(
+        "hash".to_string(),
+        "blake3",
+        vec![keep.clone(), linked.clone(), edited.clone()],
+    );
+    let mut report = Report::new();
+    hardlink_duplicates(&[group], &mut report).unwrap();
+    assert_eq!(report.links.len(), 2);
+
+    // An editor saving `c.txt` as a new file breaks the hardlink.
+    fs::remove_file(&edited).unwrap();
+    fs::write(&edited, b"edited").unwrap();
+
+    undo_link(&report.links[0]).unwrap();
+    assert!(undo_link(&report.links[1]).is_err());
+    assert_eq!(fs::read(&edited).unwrap(), b"edited");
+
+    // Reflinked copies are already independent: undo leaves them alone.
+    let reflinked = LinkRecord {
+        kind: LinkKind::Reflink,
+        ..report.links[1].clone()
+    };
+    undo_link(&reflinked).unwrap();
+    assert_eq!(fs::read(&edited).unwrap(), b"edited");
+
+    use std::os::unix::fs::MetadataExt;
+    assert_ne!(
+        fs::metadata(&keep).unwrap().ino(),
+        fs::metadata(&linked).unwrap().ino()
+    );
+}
+
+#[test]
+fn test_link_records_are_absolute() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().join("links");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("a.bin"), b"shared data").unwrap();
+    fs::write(dir.join("b.bin"), b"shared data").unwrap();
+
+    std::env::set_current_dir(temp_dir.path()).unwrap();
+    let group = DuplicateGroup::new(
+        "hash".to_string(),
+        "blake3",
+        vec![PathBuf::from("links/a.bin"), PathBuf::from("links/b.bin")],
+    );
+    let mut report = Report::new();
+    hardlink_duplicates(&[group], &mut report).unwrap();
+
+    let record = &report.links[0];
+    assert!(record.path.is_absolute());
+    assert!(record.target.is_absolute());
+
+    // Undoing works from any directory.
+    std::env::set_current_dir(&dir).unwrap();
+    undo_link(record).unwrap();
+    assert_eq!(fs::read(dir.join("b.bin")).unwrap(), b"shared data");
+}
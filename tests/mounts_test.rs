@@ -0,0 +1,44 @@
+use deduck::mounts::{Mount, fs_type_matches, parse_mounts, same_device};
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_parse_mounts_unescapes_paths() {
+    let table = "\
+proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0
+sysfs /sys sysfs rw,nosuid,nodev,noexec,relatime 0 0
+/dev/sda1 / ext4 rw,relatime 0 0
+sshfs#me@host: /home/me/My\\040Drive fuse.sshfs rw 0 0
+
+";
+    let mounts = parse_mounts(table);
+
+    assert_eq!(mounts.len(), 4);
+    assert_eq!(
+        mounts[3],
+        Mount {
+            path: PathBuf::from("/home/me/My Drive"),
+            fs_type: "fuse.sshfs".to_string(),
+        }
+    );
+    assert_eq!(mounts[0].fs_type, "proc");
+}
+
+#[test]
+fn test_fs_type_matches_subtypes() {
+    assert!(fs_type_matches("fuse", "fuse"));
+    assert!(fs_type_matches("fuse.sshfs", "fuse"));
+    assert!(!fs_type_matches("fuseblk", "fuse"));
+    assert!(!fs_type_matches("sysfs", "proc"));
+}
+
+#[test]
+fn test_same_device_within_one_directory() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("a.txt");
+    std::fs::write(&file, b"a").unwrap();
+
+    assert!(same_device(&file, dir.path()));
+    // Unknown paths cannot be checked and are not refused.
+    assert!(same_device(&dir.path().join("missing"), dir.path()));
+}
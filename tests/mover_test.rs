@@ -0,0 +1,57 @@
+use deduck::mover::{move_by_copy, move_file};
+use deduck::report::MoveMethod;
+use std::fs::{self, File, FileTimes};
+use std::time::{Duration, UNIX_EPOCH};
+use tempfile::tempdir;
+
+#[test]
+fn test_move_file_renames_within_one_filesystem() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("a.txt");
+    let dest = dir.path().join("b.txt");
+    fs::write(&src, b"content").unwrap();
+
+    assert_eq!(move_file(&src, &dest).unwrap(), MoveMethod::Rename);
+    assert!(!src.exists());
+    assert_eq!(fs::read(&dest).unwrap(), b"content");
+}
+
+#[test]
+fn test_move_by_copy_keeps_permissions_and_times() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("photo.jpg");
+    let dest = dir.path().join("moved.jpg");
+    fs::write(&src, b"pixels").unwrap();
+
+    let mtime = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    File::options()
+        .write(true)
+        .open(&src)
+        .unwrap()
+        .set_times(FileTimes::new().set_modified(mtime))
+        .unwrap();
+    let mut permissions = fs::metadata(&src).unwrap().permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(&src, permissions).unwrap();
+
+    move_by_copy(&src, &dest).unwrap();
+
+    assert!(!src.exists());
+    let metadata = fs::metadata(&dest).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"pixels");
+    assert_eq!(metadata.modified().unwrap(), mtime);
+    assert!(metadata.permissions().readonly());
+}
+
+#[test]
+fn test_move_by_copy_never_overwrites() {
+    let dir = tempdir().unwrap();
+    let src = dir.path().join("a.txt");
+    let dest = dir.path().join("b.txt");
+    fs::write(&src, b"new").unwrap();
+    fs::write(&dest, b"existing").unwrap();
+
+    assert!(move_by_copy(&src, &dest).is_err());
+    assert_eq!(fs::read(&src).unwrap(), b"new");
+    assert_eq!(fs::read(&dest).unwrap(), b"existing");
+}
@@ -0,0 +1,51 @@
+use deduck::duplicates::{DuplicateGroup, ScanResult};
+use deduck::output::{Summary, to_json};
+use deduck::report::Report;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_duplicate_group_json_and_summary() {
+    let temp_dir = tempdir().unwrap();
+    let a = temp_dir.path().join("a.bin");
+    let b = temp_dir.path().join("b.bin");
+    let c = temp_dir.path().join("c.bin");
+    for file in [&a, &b, &c] {
+        fs::write(file, b"0123456789").unwrap();
+    }
+
+    let group = DuplicateGroup::new("abc".to_string(), "blake3", vec![c, a.clone(), b]);
+    assert_eq!(group.size, 10);
+    assert_eq!(group.files[0].path, a);
+    assert_eq!(group.reclaimable(), 20);
+
+    let value: serde_json::Value = serde_json::from_str(&to_json(&group, false).unwrap()).unwrap();
+    assert_eq!(value["hash"], "abc");
+    assert_eq!(value["algorithm"], "blake3");
+    assert_eq!(value["files"].as_array().unwrap().len(), 3);
+    assert!(value["files"][0]["mtime"].is_u64());
+
+    let result = ScanResult {
+        files_found: 5,
+        duplicates: vec![group],
+        similar: vec![],
+        hardlinks: vec![],
+    };
+    let summary = Summary::new(&result);
+    assert_eq!(summary.duplicate_groups, 1);
+    assert_eq!(summary.duplicate_files, 3);
+    assert_eq!(summary.reclaimable_bytes, 20);
+}
+
+#[test]
+fn test_report_json_keeps_moved_bytes_apart_from_freed() {
+    let mut report = Report::dry_run();
+    report.add_file(PathBuf::from("/tmp/deleted.bin"), 10);
+    report.add_moved(PathBuf::from("/tmp/moved.bin"), 32);
+
+    let value: serde_json::Value = serde_json::from_str(&to_json(&report, false).unwrap()).unwrap();
+    assert_eq!(value["space_freed"], 10);
+    assert_eq!(value["space_moved"], 32);
+    assert_eq!(value["moved_files"].as_array().unwrap().len(), 1);
+}
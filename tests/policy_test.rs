@@ -0,0 +1,96 @@
+use deduck::duplicates::{DuplicateGroup, FileEntry};
+use deduck::policy::{KeepPolicy, KeepRule};
+use std::path::PathBuf;
+
+fn entry(path: &str, mtime: Option<u64>) -> FileEntry {
+    FileEntry {
+        path: PathBuf::from(path),
+        size: 0,
+        mtime,
+        protected: false,
+    }
+}
+
+fn group(files: Vec<FileEntry>) -> DuplicateGroup {
+    DuplicateGroup {
+        hash: "hash".to_string(),
+        algorithm: "blake3".to_string(),
+        size: 1,
+        files,
+    }
+}
+
+fn kept(policy: &KeepPolicy, files: Vec<FileEntry>) -> PathBuf {
+    let mut group = group(files);
+    policy.apply(&mut group);
+    group.files[0].path.clone()
+}
+
+#[test]
+fn test_keep_rules() {
+    let files = vec![
+        entry("/data/b/copy.txt", Some(200)),
+        entry("/data/archive/long/name.txt", Some(100)),
+        entry("/data/a.txt", None),
+    ];
+
+    let oldest = KeepPolicy::new(vec![KeepRule::Oldest]);
+    assert_eq!(
+        kept(&oldest, files.clone()),
+        PathBuf::from("/data/archive/long/name.txt")
+    );
+
+    let newest = KeepPolicy::new(vec![KeepRule::Newest]);
+    assert_eq!(
+        kept(&newest, files.clone()),
+        PathBuf::from("/data/b/copy.txt")
+    );
+
+    let shortest = KeepPolicy::new(vec![KeepRule::ShortestPath]);
+    assert_eq!(kept(&shortest, files.clone()), PathBuf::from("/data/a.txt"));
+
+    let longest = KeepPolicy::new(vec![KeepRule::LongestPath]);
+    assert_eq!(
+        kept(&longest, files.clone()),
+        PathBuf::from("/data/archive/long/name.txt")
+    );
+
+    let prefer = KeepPolicy::new(vec![KeepRule::Prefer(PathBuf::from("/data/b"))]);
+    assert_eq!(
+        kept(&prefer, files.clone()),
+        PathBuf::from("/data/b/copy.txt")
+    );
+
+    // No rules still gives a deterministic keeper.
+    assert_eq!(
+        kept(&KeepPolicy::default(), files),
+        PathBuf::from("/data/a.txt")
+    );
+}
+
+#[test]
+fn test_rules_compose_in_order_and_protect() {
+    let files = vec![
+        entry("/data/x/one.txt", Some(100)),
+        entry("/data/y/two.txt", Some(100)),
+        entry("/data/vault/three.txt", Some(300)),
+    ];
+
+    // Oldest ties between x and y, prefer breaks the tie; vault is protected.
+    let rules: Vec<KeepRule> = ["oldest", "prefer=/data/y", "protect=/data/vault"]
+        .iter()
+        .map(|r| r.parse().unwrap())
+        .collect();
+    let policy = KeepPolicy::new(rules);
+
+    let mut group = group(files);
+    policy.apply(&mut group);
+
+    assert_eq!(group.files[0].path, PathBuf::from("/data/y/two.txt"));
+    let redundant: Vec<_> = group.redundant().map(|f| f.path.clone()).collect();
+    assert_eq!(redundant, vec![PathBuf::from("/data/x/one.txt")]);
+    assert!(group.files.iter().any(|f| f.protected));
+    assert_eq!(group.reclaimable(), 1);
+
+    assert!("bogus".parse::<KeepRule>().is_err());
+}
@@ -0,0 +1,56 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+use deduck::quarantine::{Manifest, get_quarantine_dir, quarantine_duplicates};
+use deduck::report::Report;
+
+fn deduck(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_deduck"))
+        .args(args)
+        .env("HOME", home)
+        .env("XDG_DATA_HOME", home.join("data"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_purge_max_size_caps_instead_of_deleting_everything() {
+    let temp_dir = tempdir().unwrap();
+    let home = temp_dir.path().join("home");
+    let root = temp_dir.path().join("root");
+    fs::create_dir_all(&home).unwrap();
+    fs::create_dir_all(&root).unwrap();
+
+    let files = vec![root.join("a.txt"), root.join("b.txt")];
+    for file in &files {
+        fs::write(file, b"quarantined").unwrap();
+    }
+    let quarantine_dir = get_quarantine_dir(&root);
+    quarantine_duplicates(files, &quarantine_dir, &mut Report::new()).unwrap();
+
+    let root_arg = root.to_str().unwrap();
+    let output = deduck(&home, &["purge", "--dir", root_arg, "--max-size", "1M"]);
+    assert!(output.status.success());
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 2);
+
+    let output = deduck(&home, &["purge", "--dir", root_arg, "--min-size", "1K"]);
+    assert!(!output.status.success());
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 2);
+
+    let output = deduck(
+        &home,
+        &[
+            "purge",
+            "--dir",
+            root_arg,
+            "--max-size",
+            "1M",
+            "--max-total",
+            "2M",
+        ],
+    );
+    assert!(!output.status.success());
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 2);
+}
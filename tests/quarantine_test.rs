@@ -2,41 +2,368 @@ use std::fs::{self, File};
 use std::io::Write;
 use tempfile::tempdir;
 
-use deduck::quarantine::{quarantine_duplicates, restore_quarantined};
+use deduck::quarantine::{
+    Manifest, QuarantineLocation, Retention, expire_quarantined, get_quarantine_dir,
+    quarantine_by_root, quarantine_dirs, quarantine_duplicates, restore_quarantined, root_for,
+    summarize,
+};
+use deduck::report::{MoveMethod, Report};
+use deduck::utils::parse_duration;
 
 #[test]
 fn test_quarantine_and_restore() {
     let temp_dir = tempdir().unwrap();
     let orig_dir = temp_dir.path().join("original");
+    let nested_dir = orig_dir.join("photos").join("2021");
     let quarantine_dir = temp_dir.path().join("quarantine");
     let restore_dir = temp_dir.path().join("restore");
 
-    fs::create_dir_all(&orig_dir).unwrap();
+    fs::create_dir_all(&nested_dir).unwrap();
     fs::create_dir_all(&restore_dir).unwrap();
 
     let file1_path = orig_dir.join("file1.txt");
-    let file2_path = orig_dir.join("file2.txt");
+    let file2_path = nested_dir.join("file2.txt");
     let mut file1 = File::create(&file1_path).unwrap();
     let mut file2 = File::create(&file2_path).unwrap();
 
     writeln!(file1, "hello world").unwrap();
     writeln!(file2, "hello rust").unwrap();
 
+    let mut moves = Report::new();
     quarantine_duplicates(
         vec![file1_path.clone(), file2_path.clone()],
         &quarantine_dir,
+        &mut moves,
     )
     .unwrap();
+    assert_eq!(moves.moves.len(), 2);
+    assert!(
+        moves
+            .moves
+            .iter()
+            .all(|m| m.method == Some(MoveMethod::Rename))
+    );
 
     assert!(!file1_path.exists());
     assert!(!file2_path.exists());
-    assert!(quarantine_dir.join("file1.txt").exists());
-    assert!(quarantine_dir.join("file2.txt").exists());
 
-    restore_quarantined(&quarantine_dir, &restore_dir).unwrap();
+    let manifest = Manifest::load(&quarantine_dir).unwrap();
+    assert_eq!(manifest.entries.len(), 2);
+    for entry in &manifest.entries {
+        assert!(entry.original_path.is_absolute());
+        assert!(quarantine_dir.join(&entry.stored_name).exists());
+        assert_eq!(entry.session_id, manifest.entries[0].session_id);
+    }
 
+    // Parent directories that vanished in the meantime are recreated.
+    fs::remove_dir_all(orig_dir.join("photos")).unwrap();
+
+    let mut dry_run = Report::dry_run();
+    restore_quarantined(&quarantine_dir, &restore_dir, &mut dry_run).unwrap();
+    assert_eq!(dry_run.restored_files.len(), 2);
+    assert!(!file1_path.exists());
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 2);
+
+    let mut report = Report::new();
+    restore_quarantined(&quarantine_dir, &restore_dir, &mut report).unwrap();
+    assert_eq!(report.restored_files, dry_run.restored_files);
+
+    assert!(!quarantine_dir.exists());
+
+    assert!(file1_path.exists());
+    assert!(file2_path.exists());
+    assert_eq!(fs::read_to_string(&file2_path).unwrap(), "hello rust\n");
+    assert_eq!(fs::read_dir(&restore_dir).unwrap().count(), 0);
+}
+
+#[test]
+fn test_quarantine_same_basename_does_not_collide() {
+    let temp_dir = tempdir().unwrap();
+    let dir_a = temp_dir.path().join("a");
+    let dir_b = temp_dir.path().join("b");
+    let dir_c = temp_dir.path().join("c");
+    let quarantine_dir = temp_dir.path().join("quarantine");
+
+    for dir in [&dir_a, &dir_b, &dir_c] {
+        fs::create_dir_all(dir).unwrap();
+    }
+
+    let file_a = dir_a.join("IMG_0001.jpg");
+    let file_b = dir_b.join("IMG_0001.jpg");
+    let file_c = dir_c.join("IMG_0001.jpg");
+    fs::write(&file_a, b"same bytes").unwrap();
+    fs::write(&file_b, b"same bytes").unwrap();
+    fs::write(&file_c, b"other bytes").unwrap();
+
+    quarantine_duplicates(
+        vec![file_a.clone(), file_b.clone(), file_c.clone()],
+        &quarantine_dir,
+        &mut Report::new(),
+    )
+    .unwrap();
+
+    let manifest = Manifest::load(&quarantine_dir).unwrap();
+    assert_eq!(manifest.entries.len(), 3);
+
+    let mut stored: Vec<_> = manifest.entries.iter().map(|e| &e.stored_name).collect();
+    stored.sort();
+    stored.dedup();
+    assert_eq!(stored.len(), 3, "every quarantined file needs its own slot");
+    for entry in &manifest.entries {
+        assert!(quarantine_dir.join(&entry.stored_name).exists());
+    }
+
+    restore_quarantined(&quarantine_dir, temp_dir.path(), &mut Report::new()).unwrap();
+
+    assert_eq!(fs::read(&file_a).unwrap(), b"same bytes");
+    assert_eq!(fs::read(&file_b).unwrap(), b"same bytes");
+    assert_eq!(fs::read(&file_c).unwrap(), b"other bytes");
+}
+
+#[test]
+fn test_quarantine_by_root_uses_each_roots_folder() {
+    let temp_dir = tempdir().unwrap();
+    let downloads = temp_dir.path().join("downloads");
+    let archive = temp_dir.path().join("archive");
+    let nested = archive.join("2020");
+    fs::create_dir_all(&downloads).unwrap();
+    fs::create_dir_all(&nested).unwrap();
+
+    let from_downloads = downloads.join("a.txt");
+    let from_archive = archive.join("b.txt");
+    let from_nested = nested.join("c.txt");
+    for file in [&from_downloads, &from_archive, &from_nested] {
+        fs::write(file, b"data").unwrap();
+    }
+
+    let roots = vec![downloads.clone(), archive.clone(), nested.clone()];
+    assert_eq!(root_for(&from_nested, &roots), Some(nested.as_path()));
+
+    quarantine_by_root(
+        vec![
+            from_downloads.clone(),
+            from_archive.clone(),
+            from_nested.clone(),
+        ],
+        &roots,
+        &QuarantineLocation::InRoot,
+        &mut Report::new(),
+    )
+    .unwrap();
+
+    for root in &roots {
+        let manifest = Manifest::load(&get_quarantine_dir(root)).unwrap();
+        assert_eq!(manifest.entries.len(), 1);
+    }
+    assert_eq!(
+        quarantine_dirs(&roots, &QuarantineLocation::InRoot).len(),
+        3
+    );
+    assert_eq!(
+        quarantine_dirs(
+            &[archive.clone(), archive.join(".")],
+            &QuarantineLocation::InRoot
+        )
+        .len(),
+        1
+    );
+}
+
+#[test]
+fn test_quarantine_by_root_continues_after_a_failing_root() {
+    let temp_dir = tempdir().unwrap();
+    let broken = temp_dir.path().join("broken");
+    let healthy = temp_dir.path().join("healthy");
+    fs::create_dir_all(&broken).unwrap();
+    fs::create_dir_all(&healthy).unwrap();
+
+    // A plain file where the quarantine folder should go.
+    fs::write(get_quarantine_dir(&broken), b"not a folder").unwrap();
+    fs::write(broken.join("a.txt"), b"data").unwrap();
+    fs::write(healthy.join("b.txt"), b"data").unwrap();
+
+    let result = quarantine_by_root(
+        vec![broken.join("a.txt"), healthy.join("b.txt")],
+        &[broken.clone(), healthy.clone()],
+        &QuarantineLocation::InRoot,
+        &mut Report::new(),
+    );
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains(&broken.display().to_string()));
+    assert!(broken.join("a.txt").exists());
+    assert!(!healthy.join("b.txt").exists());
+    let manifest = Manifest::load(&get_quarantine_dir(&healthy)).unwrap();
+    assert_eq!(manifest.entries.len(), 1);
+}
+
+#[test]
+fn test_store_keeps_each_roots_quarantine_apart() {
+    let temp_dir = tempdir().unwrap();
+    let store = temp_dir.path().join("store");
+    let photos = temp_dir.path().join("a").join("photos");
+    let other_photos = temp_dir.path().join("b").join("photos");
+    fs::create_dir_all(&photos).unwrap();
+    fs::create_dir_all(&other_photos).unwrap();
+
+    let first = photos.join("one.jpg");
+    let second = other_photos.join("two.jpg");
+    fs::write(&first, b"first").unwrap();
+    fs::write(&second, b"second!").unwrap();
+
+    let location = QuarantineLocation::Store(store.clone());
+    let roots = vec![photos.clone(), other_photos.clone()];
+    let dirs = quarantine_dirs(&roots, &location);
+    assert_eq!(dirs.len(), 2);
+    assert_ne!(dirs[0], dirs[1]);
+    assert!(dirs.iter().all(|dir| dir.starts_with(&store)));
+
+    quarantine_by_root(
+        vec![first.clone(), second.clone()],
+        &roots,
+        &location,
+        &mut Report::new(),
+    )
+    .unwrap();
+    assert!(!photos.join(".deduck_quarantine").exists());
+
+    let summary = summarize(&dirs[1]).unwrap();
+    assert_eq!(summary.root, Some(fs::canonicalize(&other_photos).unwrap()));
+    assert_eq!(summary.files, 1);
+    assert_eq!(summary.bytes, 7);
+    assert!(summary.oldest.is_some());
+
+    restore_quarantined(&dirs[0], &photos, &mut Report::new()).unwrap();
+    assert_eq!(fs::read(&first).unwrap(), b"first");
+    assert!(!dirs[0].exists());
+}
+
+#[test]
+fn test_retention_expires_oldest_files_first() {
+    const DAY: u64 = 86_400;
+    let temp_dir = tempdir().unwrap();
+    let quarantine_dir = temp_dir.path().join("quarantine");
+
+    let files: Vec<_> = (0..4)
+        .map(|i| {
+            let path = temp_dir.path().join(format!("{}.bin", i));
+            fs::write(&path, vec![0u8; 100]).unwrap();
+            path
+        })
+        .collect();
+    quarantine_duplicates(files.clone(), &quarantine_dir, &mut Report::new()).unwrap();
+
+    // Quarantined 40, 20, 10 and 0 days before `now`.
+    let now = 100 * DAY;
+    let mut manifest = Manifest::load(&quarantine_dir).unwrap();
+    for (entry, age) in manifest.entries.iter_mut().zip([40, 20, 10, 0]) {
+        entry.quarantined_at = now - age * DAY;
+    }
+    manifest.save(&quarantine_dir).unwrap();
+    let dirs = vec![quarantine_dir.clone()];
+
+    let older_than = Retention {
+        older_than: Some(parse_duration("30d").unwrap()),
+        ..Retention::default()
+    };
+    let mut dry_run = Report::dry_run();
+    expire_quarantined(&dirs, &older_than, now, &mut dry_run).unwrap();
+    assert_eq!(dry_run.deleted_files, vec![files[0].clone()]);
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 4);
+
+    let mut report = Report::new();
+    expire_quarantined(&dirs, &older_than, now, &mut report).unwrap();
+    assert_eq!(report.deleted_files, dry_run.deleted_files);
+    assert_eq!(Manifest::load(&quarantine_dir).unwrap().entries.len(), 3);
+
+    let max_size = Retention {
+        max_bytes: Some(150),
+        ..Retention::default()
+    };
+    let mut report = Report::new();
+    expire_quarantined(&dirs, &max_size, now, &mut report).unwrap();
+    assert_eq!(
+        report.deleted_files,
+        vec![files[1].clone(), files[2].clone()]
+    );
+    assert_eq!(report.space_freed, 200);
+
+    let manifest = Manifest::load(&quarantine_dir).unwrap();
+    assert_eq!(manifest.entries.len(), 1);
+    assert_eq!(
+        manifest.entries[0].original_path,
+        fs::canonicalize(temp_dir.path()).unwrap().join("3.bin")
+    );
+    assert_eq!(fs::read_dir(&quarantine_dir).unwrap().count(), 2);
+
+    let everything = Retention {
+        max_bytes: Some(0),
+        ..Retention::default()
+    };
+    expire_quarantined(&dirs, &everything, now, &mut Report::new()).unwrap();
     assert!(!quarantine_dir.exists());
+}
+
+#[test]
+fn test_unreadable_file_does_not_lose_the_manifest() {
+    let temp_dir = tempdir().unwrap();
+    let quarantine_dir = temp_dir.path().join("quarantine");
+
+    let first = temp_dir.path().join("first.txt");
+    let last = temp_dir.path().join("last.txt");
+    fs::write(&first, b"first").unwrap();
+    fs::write(&last, b"last").unwrap();
+    // Exists but cannot be read as a file, even when running as root.
+    let unreadable = temp_dir.path().join("unreadable");
+    fs::create_dir(&unreadable).unwrap();
+
+    let mut report = Report::new();
+    quarantine_duplicates(
+        vec![first.clone(), unreadable.clone(), last.clone()],
+        &quarantine_dir,
+        &mut report,
+    )
+    .unwrap();
+
+    assert!(unreadable.exists());
+    let failed: Vec<_> = report.failed_moves().map(|m| m.path.clone()).collect();
+    assert_eq!(failed, vec![unreadable]);
+
+    let manifest = Manifest::load(&quarantine_dir).unwrap();
+    assert_eq!(manifest.entries.len(), 2);
+
+    restore_quarantined(&quarantine_dir, temp_dir.path(), &mut Report::new()).unwrap();
+    assert_eq!(fs::read(&first).unwrap(), b"first");
+    assert_eq!(fs::read(&last).unwrap(), b"last");
+}
+
+#[test]
+fn test_restore_continues_past_an_uncreatable_parent() {
+    let temp_dir = tempdir().unwrap();
+    let quarantine_dir = temp_dir.path().join("quarantine");
+
+    let files: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|dir| temp_dir.path().join(dir).join("file.txt"))
+        .collect();
+    for file in &files {
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(file, b"data").unwrap();
+    }
+    quarantine_duplicates(files.clone(), &quarantine_dir, &mut Report::new()).unwrap();
+
+    // The middle file's folder is gone and a plain file now takes its name.
+    let blocked = files[1].parent().unwrap();
+    fs::remove_dir(blocked).unwrap();
+    fs::write(blocked, b"in the way").unwrap();
+
+    let mut report = Report::new();
+    restore_quarantined(&quarantine_dir, temp_dir.path(), &mut report).unwrap();
 
-    assert!(restore_dir.join("file1.txt").exists());
-    assert!(restore_dir.join("file2.txt").exists());
+    assert!(files[0].exists());
+    assert!(files[2].exists());
+    assert_eq!(report.failed_moves().count(), 1);
+    let manifest = Manifest::load(&quarantine_dir).unwrap();
+    assert_eq!(manifest.entries.len(), 1);
+    assert_eq!(manifest.entries[0].original_path, files[1]);
 }
@@ -0,0 +1,44 @@
+use deduck::duplicates::DuplicateGroup;
+use deduck::reflink::{dedupe_file, reflink_duplicates};
+use deduck::report::{LinkKind, Report};
+use std::fs;
+use std::io::ErrorKind;
+use tempfile::tempdir;
+
+// Runs on whatever filesystem holds the temp dir: on btrfs/XFS the copies
+// get shared, elsewhere they must be left untouched without an error.
+#[test]
+fn test_reflink_duplicates_shares_or_falls_back() {
+    let temp_dir = tempdir().unwrap();
+    let keep = temp_dir.path().join("a.bin");
+    let copy = temp_dir.path().join("b.bin");
+    let data = vec![42u8; 64 * 1024];
+    fs::write(&keep, &data).unwrap();
+    fs::write(&copy, &data).unwrap();
+
+    let supported = match dedupe_file(&keep, &copy) {
+        Ok(_) => true,
+        Err(e) if e.kind() == ErrorKind::Unsupported => false,
+        Err(e) => panic!("unexpected error: {}", e),
+    };
+
+    let group = DuplicateGroup::new(
+        "hash".to_string(),
+        "blake3",
+        vec![keep.clone(), copy.clone()],
+    );
+    let mut report = Report::new();
+    reflink_duplicates(&[group], &mut report).unwrap();
+
+    if supported {
+        assert_eq!(report.links.len(), 1);
+        assert_eq!(report.links[0].kind, LinkKind::Reflink);
+        assert_eq!(report.links[0].size, data.len() as u64);
+    } else {
+        assert!(report.links.is_empty());
+        assert_eq!(report.space_freed, 0);
+    }
+
+    assert_eq!(fs::read(&keep).unwrap(), data);
+    assert_eq!(fs::read(&copy).unwrap(), data);
+}
@@ -0,0 +1,52 @@
+use deduck::duplicates::{DuplicateGroup, FileEntry, ScanResult};
+use deduck::review::{directory_set, removals_for_dirs, review_groups};
+use deduck::utils::format_timestamp;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+fn entry(path: &str, protected: bool) -> FileEntry {
+    FileEntry {
+        path: PathBuf::from(path),
+        size: 0,
+        mtime: Some(0),
+        protected,
+    }
+}
+
+#[test]
+fn test_directory_choice_applies_to_matching_groups() {
+    let result = ScanResult {
+        files_found: 5,
+        duplicates: vec![DuplicateGroup {
+            hash: "h".to_string(),
+            algorithm: "blake3".to_string(),
+            size: 3,
+            files: vec![
+                entry("/photos/a.jpg", false),
+                entry("/backup/a.jpg", false),
+                entry("/backup/a (1).jpg", true),
+            ],
+        }],
+        similar: vec![],
+        hardlinks: vec![],
+    };
+
+    let groups = review_groups(&result);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].files.len(), 3);
+
+    let dirs = directory_set(&groups[0].files);
+    let expected: BTreeSet<PathBuf> = ["/photos", "/backup"].iter().map(PathBuf::from).collect();
+    assert_eq!(dirs, expected);
+
+    let kept_dirs: BTreeSet<PathBuf> = [PathBuf::from("/photos")].into_iter().collect();
+    let removals = removals_for_dirs(&groups[0].files, &kept_dirs);
+    assert_eq!(removals, vec![PathBuf::from("/backup/a.jpg")]);
+}
+
+#[test]
+fn test_format_timestamp() {
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+    assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+}
@@ -1,5 +1,8 @@
-use deduck::scanner::scan_directory;
-use std::fs::File;
+use deduck::scanner::{
+    IgnoreRules, WalkOptions, distinct_roots, scan_directories, scan_directory, scan_directory_with,
+};
+use std::fs::{self, File};
+use std::path::Path;
 use tempfile::tempdir;
 
 #[test]
@@ -21,3 +24,242 @@ fn test_scan_directory_basic() {
     found.sort();
     assert_eq!(found, vec!["file1.txt", "file2.txt"]);
 }
+
+fn scanned_names(dir: &Path, rules: &IgnoreRules) -> Vec<String> {
+    let mut found: Vec<String> = scan_directory_with(dir, rules)
+        .unwrap()
+        .iter()
+        .map(|p| {
+            p.strip_prefix(dir)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    found.sort();
+    found
+}
+
+#[test]
+fn test_scan_honors_ignore_rules() {
+    let dir = tempdir().unwrap();
+    let root = dir.path();
+    for sub in [
+        ".deduck_quarantine",
+        ".git",
+        "node_modules/pkg",
+        "src",
+        "build",
+    ] {
+        fs::create_dir_all(root.join(sub)).unwrap();
+    }
+    for file in [
+        ".deduck_quarantine/old.txt",
+        ".git/HEAD",
+        "node_modules/pkg/index.js",
+        "src/main.rs",
+        "src/notes.tmp",
+        "build/out.bin",
+        "photo.jpg",
+    ] {
+        File::create(root.join(file)).unwrap();
+    }
+    fs::write(root.join(".deduckignore"), "node_modules/\n").unwrap();
+    fs::write(root.join("src").join(".deduckignore"), "*.tmp\n").unwrap();
+    fs::write(root.join(".gitignore"), "build/\n").unwrap();
+
+    assert_eq!(
+        scanned_names(root, &IgnoreRules::default()),
+        vec![
+            ".deduckignore",
+            ".git/HEAD",
+            ".gitignore",
+            "build/out.bin",
+            "photo.jpg",
+            "src/.deduckignore",
+            "src/main.rs",
+        ]
+    );
+
+    let rules = IgnoreRules {
+        excludes: vec!["*.jpg".to_string()],
+        gitignore: true,
+        ..IgnoreRules::default()
+    };
+    assert_eq!(
+        scanned_names(root, &rules),
+        vec![
+            ".deduckignore",
+            ".gitignore",
+            "src/.deduckignore",
+            "src/main.rs"
+        ]
+    );
+
+    let rules = IgnoreRules {
+        includes: vec!["*.rs".to_string(), "*.jpg".to_string()],
+        ..IgnoreRules::default()
+    };
+    assert_eq!(
+        scanned_names(root, &rules),
+        vec!["photo.jpg", "src/main.rs"]
+    );
+}
+
+#[test]
+fn test_scan_rejects_invalid_glob() {
+    let dir = tempdir().unwrap();
+    let rules = IgnoreRules {
+        excludes: vec!["a{b".to_string()],
+        ..IgnoreRules::default()
+    };
+    let err = scan_directory_with(dir.path(), &rules).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_scan_directories_merges_overlapping_roots() {
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a");
+    let nested = a.join("nested");
+    let b = dir.path().join("b");
+    fs::create_dir_all(&nested).unwrap();
+    fs::create_dir_all(&b).unwrap();
+    File::create(a.join("one.txt")).unwrap();
+    File::create(nested.join("two.txt")).unwrap();
+    File::create(b.join("three.txt")).unwrap();
+
+    let roots = vec![nested.clone(), a.clone(), b.clone(), b.join(".")];
+    assert_eq!(distinct_roots(&roots), vec![a.clone(), b.clone()]);
+
+    let found = scan_directories(&roots, &IgnoreRules::default(), &WalkOptions::default()).unwrap();
+    assert_eq!(found.paths_found, 3);
+    assert_eq!(
+        found.files,
+        vec![
+            a.join("nested").join("two.txt"),
+            a.join("one.txt"),
+            b.join("three.txt")
+        ]
+    );
+}
+
+#[test]
+fn test_scan_directories_collapses_hardlinks() {
+    let dir = tempdir().unwrap();
+    let original = dir.path().join("a.txt");
+    fs::write(&original, b"same inode").unwrap();
+    fs::hard_link(&original, dir.path().join("b.txt")).unwrap();
+    fs::write(dir.path().join("c.txt"), b"same inode").unwrap();
+
+    let roots = vec![dir.path().to_path_buf()];
+    let found = scan_directories(&roots, &IgnoreRules::default(), &WalkOptions::default()).unwrap();
+
+    assert_eq!(found.paths_found, 3);
+    assert_eq!(
+        found.files,
+        vec![original.clone(), dir.path().join("c.txt")]
+    );
+    assert_eq!(
+        found.hardlinks,
+        vec![vec![original, dir.path().join("b.txt")]]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_follow_symlinks_skips_loops() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    fs::create_dir(&sub).unwrap();
+    fs::write(sub.join("file.txt"), b"data").unwrap();
+    symlink(dir.path(), sub.join("loop")).unwrap();
+    symlink(sub.join("file.txt"), dir.path().join("alias.txt")).unwrap();
+
+    let roots = vec![dir.path().to_path_buf()];
+    let found = scan_directories(&roots, &IgnoreRules::default(), &WalkOptions::default()).unwrap();
+    assert_eq!(found.files, vec![sub.join("file.txt")]);
+
+    let walk = WalkOptions {
+        follow_symlinks: true,
+        ..WalkOptions::default()
+    };
+    let found = scan_directories(&roots, &IgnoreRules::default(), &walk).unwrap();
+    assert_eq!(found.files, vec![dir.path().join("alias.txt")]);
+    assert_eq!(
+        found.hardlinks,
+        vec![vec![dir.path().join("alias.txt"), sub.join("file.txt")]]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_scan_skips_fifos_and_sockets() {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::UnixListener;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("regular.txt"), b"data").unwrap();
+
+    let fifo = dir.path().join("pipe");
+    let c_path = CString::new(fifo.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o644) }, 0);
+    let _socket = UnixListener::bind(dir.path().join("socket")).unwrap();
+
+    let found = scan_directory(dir.path()).unwrap();
+    assert_eq!(found, vec![dir.path().join("regular.txt")]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_skip_fs_types_and_one_file_system() {
+    let dir = tempdir().unwrap();
+    let sub = dir.path().join("sub");
+    fs::create_dir(&sub).unwrap();
+    fs::write(dir.path().join("top.txt"), b"top").unwrap();
+    fs::write(sub.join("nested.txt"), b"nested").unwrap();
+    let roots = vec![dir.path().to_path_buf()];
+
+    let walk = WalkOptions {
+        one_file_system: true,
+        ..WalkOptions::default()
+    };
+    let found = scan_directories(&roots, &IgnoreRules::default(), &walk).unwrap();
+    assert_eq!(found.files.len(), 2);
+
+    // Skipping the type of the filesystem the temp dir lives on prunes every
+    // directory below the root.
+    let canonical = fs::canonicalize(dir.path()).unwrap();
+    let Some(mount) = deduck::mounts::mounts()
+        .into_iter()
+        .filter(|m| canonical.starts_with(&m.path))
+        .max_by_key(|m| m.path.components().count())
+    else {
+        return;
+    };
+    let walk = WalkOptions {
+        skip_fs_types: vec![mount.fs_type],
+        ..WalkOptions::default()
+    };
+    let found = scan_directories(&roots, &IgnoreRules::default(), &walk).unwrap();
+    assert_eq!(found.files, vec![dir.path().join("top.txt")]);
+}
+
+#[test]
+fn test_scan_skips_configured_dirs() {
+    let dir = tempdir().unwrap();
+    let store = dir.path().join("store");
+    fs::create_dir(&store).unwrap();
+    fs::write(store.join("quarantined.txt"), b"old").unwrap();
+    fs::write(dir.path().join("kept.txt"), b"new").unwrap();
+
+    let rules = IgnoreRules {
+        skip_dirs: vec![store],
+        ..IgnoreRules::default()
+    };
+    let found = scan_directory_with(dir.path(), &rules).unwrap();
+    assert_eq!(found, vec![dir.path().join("kept.txt")]);
+}
@@ -0,0 +1,133 @@
+use deduck::duplicates::find_duplicates;
+use deduck::options::{ScanMode, ScanOptions};
+use deduck::session::Session;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn test_verify_skips_files_changed_since_filter() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+
+    fs::write(root.join("a1.txt"), b"group a").unwrap();
+    fs::write(root.join("a2.txt"), b"group a").unwrap();
+    fs::write(root.join("a3.txt"), b"group a").unwrap();
+    fs::write(root.join("b1.txt"), b"group bb").unwrap();
+    fs::write(root.join("b2.txt"), b"group bb").unwrap();
+
+    let result = find_duplicates(&ScanOptions::new(root)).unwrap();
+    let roots = vec![root.to_path_buf()];
+    let session = Session::new(&roots, ScanMode::Normal, result);
+    assert!(session.is_for(&roots));
+
+    // Same size and (likely) same mtime second, different content.
+    fs::write(root.join("a3.txt"), b"group A").unwrap();
+    // The kept copy of group b disappears.
+    fs::remove_file(root.join("b1.txt")).unwrap();
+
+    let mut candidates = session.result.redundant_files();
+    candidates.push(root.join("unknown.txt"));
+    let (verified, skipped) = session.verify(candidates, &session.result.kept_files());
+
+    assert_eq!(verified, vec![root.join("a2.txt")]);
+
+    let reasons: Vec<(String, &str)> = skipped
+        .iter()
+        .map(|s| {
+            let name = s.path.file_name().unwrap().to_string_lossy().to_string();
+            (name, s.reason.as_str())
+        })
+        .collect();
+    assert_eq!(reasons.len(), 3);
+    assert!(reasons.iter().any(|(name, reason)| name == "a3.txt"
+        && (*reason == "changed content" || *reason == "was modified")));
+    assert!(
+        reasons
+            .iter()
+            .any(|(name, reason)| name == "b2.txt" && reason.ends_with("no longer exists"))
+    );
+    assert!(
+        reasons
+            .iter()
+            .any(|(name, reason)| name == "unknown.txt" && reason.contains("not part"))
+    );
+}
+
+#[test]
+fn test_session_round_trips_through_json() {
+    let temp = tempdir().unwrap();
+    fs::write(temp.path().join("x.bin"), b"xx").unwrap();
+    fs::write(temp.path().join("y.bin"), b"xx").unwrap();
+
+    let result = find_duplicates(&ScanOptions::new(temp.path())).unwrap();
+    let session = Session::new(&[temp.path().to_path_buf()], ScanMode::Quick, result);
+
+    let json = serde_json::to_string(&session).unwrap();
+    let loaded: Session = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded.mode, ScanMode::Quick);
+    assert_eq!(loaded.result.duplicates.len(), 1);
+    assert_eq!(loaded.result.duplicates[0].files[1].size, 2);
+    let (verified, skipped) =
+        loaded.verify(loaded.result.redundant_files(), &loaded.result.kept_files());
+    assert_eq!(verified.len(), 1);
+    assert!(skipped.is_empty());
+}
+
+#[test]
+fn test_verify_checks_the_copies_chosen_to_keep() {
+    let temp = tempdir().unwrap();
+    let root = temp.path();
+
+    fs::write(root.join("a1.txt"), b"group a").unwrap();
+    fs::write(root.join("a2.txt"), b"group a").unwrap();
+    fs::write(root.join("a3.txt"), b"group a").unwrap();
+
+    let result = find_duplicates(&ScanOptions::new(root)).unwrap();
+    let session = Session::new(&[root.to_path_buf()], ScanMode::Normal, result);
+
+    // Keep a3 instead of the suggested a1, then change a3.
+    let kept: HashSet<_> = [root.join("a3.txt")].into_iter().collect();
+    fs::write(root.join("a3.txt"), b"group A").unwrap();
+
+    let candidates = vec![root.join("a1.txt"), root.join("a2.txt")];
+    let (verified, skipped) = session.verify(candidates.clone(), &kept);
+    assert!(verified.is_empty());
+    assert_eq!(skipped.len(), 2);
+    assert!(skipped.iter().all(|s| s.reason.starts_with("kept copy")));
+
+    // A group with no kept copy is never emptied.
+    let (verified, skipped) = session.verify(candidates, &HashSet::new());
+    assert!(verified.is_empty());
+    assert!(skipped.iter().all(|s| s.reason == "no copy of it is kept"));
+}
+
+#[test]
+fn test_verify_from_another_working_directory() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("root");
+    let elsewhere = temp.path().join("elsewhere");
+    for dir in [&root, &elsewhere] {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("x.txt"), b"same").unwrap();
+        fs::write(dir.join("y.txt"), b"same").unwrap();
+    }
+
+    std::env::set_current_dir(&root).unwrap();
+    let result = find_duplicates(&ScanOptions::new(".")).unwrap();
+    let session = Session::new(&[PathBuf::from(".")], ScanMode::Normal, result);
+    assert!(session.result.duplicates[0].files[0].path.is_absolute());
+
+    // A mirror with the same relative paths must not be mistaken for the root.
+    std::env::set_current_dir(&elsewhere).unwrap();
+    fs::remove_file(root.join("x.txt")).unwrap();
+    let (verified, skipped) = session.verify(
+        session.result.redundant_files(),
+        &session.result.kept_files(),
+    );
+    assert!(verified.is_empty());
+    assert_eq!(skipped.len(), 1);
+    assert!(skipped[0].reason.contains(&root.display().to_string()));
+}
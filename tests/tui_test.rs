@@ -0,0 +1,100 @@
+use deduck::duplicates::{DuplicateGroup, FileEntry, ScanResult};
+use deduck::tui::{App, Focus, Mark, Outcome};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::PathBuf;
+
+fn entry(path: &str) -> FileEntry {
+    FileEntry {
+        path: PathBuf::from(path),
+        size: 0,
+        mtime: None,
+        protected: false,
+    }
+}
+
+fn group(hash: &str, size: u64, paths: &[&str]) -> DuplicateGroup {
+    DuplicateGroup {
+        hash: hash.to_string(),
+        algorithm: "blake3".to_string(),
+        size,
+        files: paths.iter().map(|p| entry(p)).collect(),
+    }
+}
+
+fn press(app: &mut App, code: KeyCode) -> Option<Outcome> {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn sample() -> ScanResult {
+    ScanResult {
+        files_found: 5,
+        duplicates: vec![
+            group("small", 10, &["/music/a.mp3", "/music/b.mp3"]),
+            group(
+                "big",
+                1000,
+                &["/video/x.mp4", "/backup/x.mp4", "/old/x.mp4"],
+            ),
+        ],
+        similar: vec![],
+        hardlinks: vec![],
+    }
+}
+
+#[test]
+fn test_groups_sorted_by_reclaimable_space() {
+    let app = App::new(&sample());
+    assert_eq!(app.groups[0].reclaimable(), 2000);
+    assert_eq!(app.groups[1].reclaimable(), 10);
+    assert_eq!(app.marked_files().len(), 3);
+}
+
+#[test]
+fn test_filter_mark_and_confirm() {
+    let mut app = App::new(&sample());
+
+    press(&mut app, KeyCode::Char('/'));
+    for c in "MUSIC".chars() {
+        press(&mut app, KeyCode::Char(c));
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.visible.len(), 1);
+    assert_eq!(
+        app.selected_group().unwrap().files[0].entry.path,
+        PathBuf::from("/music/a.mp3")
+    );
+
+    // Keep b.mp3 instead of a.mp3, then try to mark the only remaining keeper.
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.focus, Focus::Files);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Char(' '));
+    let files = &app.selected_group().unwrap().files;
+    assert_eq!(files[0].mark, Mark::Remove);
+    assert_eq!(files[1].mark, Mark::Keep);
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    assert_eq!(app.selected_group().unwrap().files[1].mark, Mark::Keep);
+
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('c'));
+    assert_eq!(app.focus, Focus::Confirm);
+
+    // Marks in groups hidden by the filter still count.
+    let outcome = press(&mut app, KeyCode::Char('y')).unwrap();
+    let Outcome::Quarantine(mut files) = outcome else {
+        panic!("expected quarantine outcome");
+    };
+    files.sort();
+    assert_eq!(
+        files,
+        vec![
+            PathBuf::from("/backup/x.mp4"),
+            PathBuf::from("/music/a.mp3"),
+            PathBuf::from("/old/x.mp4"),
+        ]
+    );
+}
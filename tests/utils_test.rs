@@ -0,0 +1,11 @@
+use deduck::utils::parse_duration;
+
+#[test]
+fn test_parse_duration_requires_a_unit() {
+    assert_eq!(parse_duration("30d"), Ok(30 * 86_400));
+    assert_eq!(parse_duration("12h"), Ok(12 * 3_600));
+    assert_eq!(parse_duration("2W"), Ok(2 * 604_800));
+    assert!(parse_duration("30").is_err());
+    assert!(parse_duration("1.5d").is_err());
+    assert!(parse_duration("d").is_err());
+}